@@ -0,0 +1,135 @@
+use crate::data_formats::{Evaluation, ManualCodeExplanation, ScoutFile, TeamSide};
+use crate::statistics::{efficiency, percentage, EvaluationCounts};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttackStatistics {
+    pub evaluations: EvaluationCounts,
+}
+
+impl AttackStatistics {
+    pub fn add(&mut self, attack: &ManualCodeExplanation) {
+        self.evaluations.add(attack.evaluation);
+    }
+
+    pub fn merge(&mut self, other: &AttackStatistics) {
+        self.evaluations.merge(&other.evaluations);
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.evaluations.total()
+    }
+
+    pub fn kills(&self) -> u32 {
+        self.evaluations.hashtag
+    }
+
+    pub fn errors(&self) -> u32 {
+        self.evaluations.equal
+    }
+
+    pub fn blocked(&self) -> u32 {
+        self.evaluations.slash
+    }
+
+    pub fn kill_percentage(&self) -> Option<f64> {
+        percentage(self.kills(), self.attempts())
+    }
+
+    // Errors only, blocked attacks are counted separately
+    pub fn error_percentage(&self) -> Option<f64> {
+        percentage(self.errors(), self.attempts())
+    }
+
+    // (kills - errors - blocked) / attempts, from -100 to 100
+    pub fn efficiency(&self) -> Option<f64> {
+        efficiency(
+            self.kills(),
+            self.errors() + self.blocked(),
+            self.attempts(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttackGroup<K> {
+    pub key: K,
+    pub statistics: AttackStatistics,
+}
+
+pub(crate) fn group_entry<K: PartialEq>(
+    groups: &mut Vec<AttackGroup<K>>,
+    key: K,
+) -> &mut AttackStatistics {
+    let index = match groups.iter().position(|group| group.key == key) {
+        Some(index) => index,
+        None => {
+            groups.push(AttackGroup {
+                key,
+                statistics: AttackStatistics::default(),
+            });
+            groups.len() - 1
+        }
+    };
+
+    &mut groups[index].statistics
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttackReport {
+    pub team: TeamSide,
+    pub total: AttackStatistics,
+    // Keyed by the code in [3ATTACKCOMBINATION], None for attacks without a combination
+    pub by_combination: Vec<AttackGroup<Option<String>>>,
+    // Keyed by the setter call of the set before the attack, see [3SETTERCALL]
+    pub by_setter_call: Vec<AttackGroup<Option<String>>>,
+    // Keyed by the reception of the same possession, None for transition attacks
+    pub by_reception: Vec<AttackGroup<Option<Evaluation>>>,
+    pub by_blockers: Vec<AttackGroup<Option<u8>>>,
+}
+
+pub fn attack_statistics(scout_file: &ScoutFile) -> Vec<AttackReport> {
+    let mut reports = TeamSide::ALL
+        .iter()
+        .map(|&team| AttackReport {
+            team,
+            total: AttackStatistics::default(),
+            by_combination: Vec::new(),
+            by_setter_call: Vec::new(),
+            by_reception: Vec::new(),
+            by_blockers: Vec::new(),
+        })
+        .collect::<Vec<AttackReport>>();
+
+    for rally in scout_file.rallies() {
+        for possession in rally.possessions() {
+            let report = match possession.team {
+                TeamSide::Home => &mut reports[0],
+                TeamSide::Visiting => &mut reports[1],
+            };
+
+            let reception = possession.reception().map(|reception| reception.evaluation);
+
+            for (index, attack) in possession.attacks() {
+                let setter_call = possession
+                    .set_before(index)
+                    .and_then(|set| set.setter_call())
+                    .map(str::to_string);
+
+                report.total.add(attack);
+                group_entry(
+                    &mut report.by_combination,
+                    attack.attack_combination().map(str::to_string),
+                )
+                .add(attack);
+                group_entry(&mut report.by_setter_call, setter_call).add(attack);
+                group_entry(&mut report.by_reception, reception).add(attack);
+                group_entry(&mut report.by_blockers, attack.blockers()).add(attack);
+            }
+        }
+    }
+
+    reports
+}
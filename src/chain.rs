@@ -0,0 +1,159 @@
+use crate::attacks::AttackStatistics;
+use crate::data_formats::{Evaluation, ManualCodeExplanation, ScoutFile, Skill, TeamSide};
+use crate::rally::Possession;
+
+// The touches of one possession by role, e.g. reception, set and attack of a side-out
+#[derive(Debug, Clone, PartialEq)]
+pub struct PossessionChain<'a> {
+    // Index into the rallies of `ScoutFile::rallies()`
+    pub rally_index: usize,
+    pub set_number: u8,
+    pub team: TeamSide,
+    // None when the rally has no point code
+    pub won: Option<bool>,
+    // Serve of the opponent for the first possession of the receiving team, or the team's own
+    // serve for the possession that is just the serve
+    pub serve: Option<&'a ManualCodeExplanation>,
+    pub reception: Option<&'a ManualCodeExplanation>,
+    // The block that started the possession
+    pub block: Option<&'a ManualCodeExplanation>,
+    pub dig: Option<&'a ManualCodeExplanation>,
+    pub free_ball: Option<&'a ManualCodeExplanation>,
+    // The last set before the attack, the last set of the possession without an attack
+    pub set: Option<&'a ManualCodeExplanation>,
+    pub attack: Option<&'a ManualCodeExplanation>,
+    // First block and dig of the next possession, i.e. the opponent's answer to the attack
+    pub opponent_block: Option<&'a ManualCodeExplanation>,
+    pub opponent_dig: Option<&'a ManualCodeExplanation>,
+    pub touches: Vec<&'a ManualCodeExplanation>,
+}
+
+impl<'a> PossessionChain<'a> {
+    fn new(
+        rally_index: usize,
+        set_number: u8,
+        won: Option<bool>,
+        possession: &Possession<'a>,
+        previous: Option<&Possession<'a>>,
+        next: Option<&Possession<'a>>,
+    ) -> PossessionChain<'a> {
+        let first = |skill: Skill| {
+            possession
+                .touches
+                .iter()
+                .copied()
+                .find(|touch| touch.skill == skill)
+        };
+        let attack = possession.attacks().next();
+        let set = match attack {
+            Some((index, _)) => possession.set_before(index),
+            None => possession.set_before(possession.touches.len()),
+        };
+        let next_first = |skill: Skill| {
+            next.and_then(|next| {
+                next.touches
+                    .iter()
+                    .copied()
+                    .find(|touch| touch.skill == skill)
+            })
+        };
+
+        PossessionChain {
+            rally_index,
+            set_number,
+            team: possession.team,
+            won,
+            serve: first(Skill::Serve).or_else(|| {
+                previous
+                    .filter(|_| possession.reception().is_some())
+                    .and_then(|previous| {
+                        previous
+                            .touches
+                            .iter()
+                            .copied()
+                            .find(|touch| touch.skill == Skill::Serve)
+                    })
+            }),
+            reception: possession.reception(),
+            block: possession
+                .touches
+                .first()
+                .copied()
+                .filter(|touch| touch.skill == Skill::Block),
+            dig: first(Skill::Dig),
+            free_ball: first(Skill::FreeBall),
+            set,
+            attack: attack.map(|(_, attack)| attack),
+            opponent_block: next_first(Skill::Block),
+            opponent_dig: next_first(Skill::Dig),
+            touches: possession.touches.clone(),
+        }
+    }
+
+    pub fn reception_evaluation(&self) -> Option<Evaluation> {
+        self.reception.map(|reception| reception.evaluation)
+    }
+
+    // Setter call of the set, e.g. `K1`
+    pub fn setter_call(&self) -> Option<&'a str> {
+        self.set.and_then(|set| set.setter_call())
+    }
+
+    // Whether the setter call of the set has a quick run in its `[3SETTERCALL]` definition. None
+    // when the set has no call or the file does not define it, e.g. `KB` in the test file.
+    pub fn quick_available(&self, scout_file: &ScoutFile) -> Option<bool> {
+        let call = scout_file.setter_call(self.setter_call()?)?;
+
+        Some(!call.quick_run.is_empty())
+    }
+
+    pub fn attack_combination(&self) -> Option<&'a str> {
+        self.attack.and_then(|attack| attack.attack_combination())
+    }
+
+    pub fn blockers(&self) -> Option<u8> {
+        self.attack.and_then(|attack| attack.blockers())
+    }
+
+    // The possession started with the reception of a serve
+    pub fn is_side_out(&self) -> bool {
+        self.reception.is_some()
+    }
+}
+
+// One chain per possession of every rally, in order
+pub fn possession_chains(scout_file: &ScoutFile) -> Vec<PossessionChain<'_>> {
+    let mut chains = Vec::new();
+
+    for (rally_index, rally) in scout_file.rallies().iter().enumerate() {
+        let possessions = rally.possessions();
+        let winner = rally.winner();
+
+        for (index, possession) in possessions.iter().enumerate() {
+            chains.push(PossessionChain::new(
+                rally_index,
+                rally.set,
+                winner.map(|winner| winner == possession.team),
+                possession,
+                index.checked_sub(1).map(|previous| &possessions[previous]),
+                possessions.get(index + 1),
+            ));
+        }
+    }
+
+    chains
+}
+
+// Statistics of the attacks of the chains, e.g. after filtering on `reception_evaluation` and
+// `quick_available`
+pub fn chain_attack_statistics<'a, 'b: 'a>(
+    chains: impl IntoIterator<Item = &'a PossessionChain<'b>>,
+) -> AttackStatistics {
+    let mut statistics = AttackStatistics::default();
+
+    for attack in chains.into_iter().filter_map(|chain| chain.attack) {
+        statistics.add(attack);
+    }
+
+    statistics
+}
@@ -0,0 +1,244 @@
+use std::io;
+
+use crate::data_formats::{Player, ScoutFile, Team, TeamSide};
+use crate::datetime::Date;
+use crate::identity::IdentityResolver;
+use crate::statistics::{player_statistics, team_statistics, SkillCounts, TeamStatistics};
+
+// Matches to keep, None matches everything. Matches without a date are left out as soon as a
+// date bound is set.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchFilter {
+    // Team id or team name of the opponent
+    pub opponent: Option<String>,
+    // Inclusive bounds on `Game::date`
+    pub from: Option<Date>,
+    pub to: Option<Date>,
+    pub competition: Option<String>,
+    pub season: Option<String>,
+}
+
+impl MatchFilter {
+    pub fn matches(&self, scout_file: &ScoutFile, side: TeamSide) -> bool {
+        let opponent = scout_file.team(side.opponent());
+
+        if let Some(name) = &self.opponent {
+            if opponent.team_id != *name && !opponent.team_name.eq_ignore_ascii_case(name) {
+                return false;
+            }
+        }
+
+        let date = scout_file.game.date;
+
+        if self
+            .from
+            .is_some_and(|from| date.map_or(true, |date| date < from))
+            || self
+                .to
+                .is_some_and(|to| date.map_or(true, |date| date > to))
+        {
+            return false;
+        }
+
+        if let Some(competition) = &self.competition {
+            if !scout_file
+                .game
+                .competition
+                .eq_ignore_ascii_case(competition)
+            {
+                return false;
+            }
+        }
+
+        if let Some(season) = &self.season {
+            if scout_file.game.season != *season {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchTeamStatistics {
+    // Index into `MatchCollection::matches`
+    pub match_index: usize,
+    pub date: Option<Date>,
+    pub side: TeamSide,
+    pub opponent: Team,
+    pub statistics: TeamStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollectionTeamReport {
+    pub team_id: String,
+    pub total: TeamStatistics,
+    pub matches: Vec<MatchTeamStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchPlayerStatistics {
+    pub match_index: usize,
+    pub date: Option<Date>,
+    // Shirt number worn in that match
+    pub player_number: u8,
+    pub skills: SkillCounts,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CollectionPlayerReport {
+    // Key of the `PlayerIdentity`, or `#7` for a shirt number that is not on the roster
+    pub key: String,
+    // Roster entry of the last match the player appeared in, None for shirt numbers that are
+    // only found in codes
+    pub player: Option<Player>,
+    // Every shirt number the player wore, in order of appearance
+    pub player_numbers: Vec<u8>,
+    pub total: SkillCounts,
+    pub matches: Vec<MatchPlayerStatistics>,
+}
+
+// Many scout files, typically the matches of a season
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MatchCollection {
+    pub matches: Vec<ScoutFile>,
+}
+
+impl MatchCollection {
+    pub fn new(matches: Vec<ScoutFile>) -> MatchCollection {
+        MatchCollection { matches }
+    }
+
+    // Reads every file, failing on the first one that cannot be read
+    pub fn from_files(file_names: &[&str]) -> Result<MatchCollection, io::Error> {
+        let matches = file_names
+            .iter()
+            .map(|file_name| crate::read_from_file(file_name))
+            .collect::<Result<Vec<ScoutFile>, io::Error>>()?;
+
+        Ok(MatchCollection::new(matches))
+    }
+
+    pub fn push(&mut self, scout_file: ScoutFile) {
+        self.matches.push(scout_file);
+    }
+
+    // Matches played by `team_id` that pass the filter, with their index and the team's side
+    pub fn matches_of<'a>(
+        &'a self,
+        team_id: &'a str,
+        filter: &'a MatchFilter,
+    ) -> impl Iterator<Item = (usize, &'a ScoutFile, TeamSide)> + 'a {
+        self.matches
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, scout_file)| {
+                let side = scout_file.side_of(team_id)?;
+
+                filter
+                    .matches(scout_file, side)
+                    .then_some((index, scout_file, side))
+            })
+    }
+
+    pub fn team_report(&self, team_id: &str, filter: &MatchFilter) -> CollectionTeamReport {
+        let mut report = CollectionTeamReport {
+            team_id: team_id.to_string(),
+            total: TeamStatistics::default(),
+            matches: Vec::new(),
+        };
+
+        for (match_index, scout_file, side) in self.matches_of(team_id, filter) {
+            let statistics = team_statistics(scout_file)
+                .into_iter()
+                .find(|team| team.team == side)
+                .map(|team| team.match_statistics)
+                .unwrap_or_default();
+
+            report.total.merge(&statistics);
+            report.matches.push(MatchTeamStatistics {
+                match_index,
+                date: scout_file.game.date,
+                side,
+                opponent: scout_file.team(side.opponent()).clone(),
+                statistics,
+            });
+        }
+
+        report
+    }
+
+    // One report per player of `team_id`, in order of first appearance
+    pub fn player_reports(
+        &self,
+        team_id: &str,
+        filter: &MatchFilter,
+    ) -> Vec<CollectionPlayerReport> {
+        self.player_reports_with(team_id, filter, &mut IdentityResolver::default())
+    }
+
+    // Like `player_reports`, with a resolver that can carry overrides and identities from
+    // earlier collections
+    pub fn player_reports_with(
+        &self,
+        team_id: &str,
+        filter: &MatchFilter,
+        resolver: &mut IdentityResolver,
+    ) -> Vec<CollectionPlayerReport> {
+        let mut reports: Vec<CollectionPlayerReport> = Vec::new();
+
+        for (match_index, scout_file, side) in self.matches_of(team_id, filter) {
+            let keys = resolver.resolve_file(scout_file);
+
+            for statistics in player_statistics(scout_file)
+                .into_iter()
+                .filter(|statistics| statistics.team == side)
+            {
+                let key = keys
+                    .get(&(side, statistics.player_number))
+                    .cloned()
+                    .unwrap_or_else(|| format!("#{}", statistics.player_number));
+
+                let index = match reports.iter().position(|report| report.key == key) {
+                    Some(index) => index,
+                    None => {
+                        reports.push(CollectionPlayerReport {
+                            key,
+                            player: None,
+                            player_numbers: Vec::new(),
+                            total: SkillCounts::default(),
+                            matches: Vec::new(),
+                        });
+                        reports.len() - 1
+                    }
+                };
+
+                let report = &mut reports[index];
+
+                if statistics.player.is_some() {
+                    report.player = statistics.player.clone();
+                }
+
+                if !report.player_numbers.contains(&statistics.player_number) {
+                    report.player_numbers.push(statistics.player_number);
+                }
+
+                report.total.merge(&statistics.skills);
+                report.matches.push(MatchPlayerStatistics {
+                    match_index,
+                    date: scout_file.game.date,
+                    player_number: statistics.player_number,
+                    skills: statistics.skills,
+                });
+            }
+        }
+
+        reports
+    }
+}
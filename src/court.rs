@@ -0,0 +1,147 @@
+use std::fmt;
+
+// Data Volley places coordinates on a 100 x 100 grid written as `YYXX`. The net runs along row
+// 50 and the centre line along column 50. The size of a cell is not published, it comes from the
+// zones Data Volley writes next to each coordinate. In `tests/test.dvw` the 3 m line falls
+// between rows 63 and 65, the 6 m line between rows 76 and 77 and the zone columns split between
+// columns 36 and 38 and between 62 and 63. That gives rows of 22.5 cm and columns of 12 cm, so the
+// court spans rows 10 to 90 and columns 12.5 to 87.5 and the rest of the grid is the free zone.
+// The default `X5` and `X6` combinations, attacks at the antennas, are drawn on columns 12 and 88.
+pub(crate) const NET_ROW: f64 = 50.0;
+pub(crate) const CENTRE_COLUMN: f64 = 50.0;
+pub(crate) const ROW_METRES: f64 = 0.225;
+pub(crate) const COLUMN_METRES: f64 = 0.12;
+// Distance from the net to the far edge of the grid, the back zones reach it
+pub(crate) const HALF_LENGTH: f64 = NET_ROW * ROW_METRES;
+pub(crate) const HALF_WIDTH: f64 = CENTRE_COLUMN * COLUMN_METRES;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum CourtHalf {
+    // Rows below the net, drawn at the bottom of the Data Volley court
+    Near,
+    Far,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CourtCoordinate {
+    // Column, 0 to 99 from left to right as seen from the near half
+    pub x: u8,
+    // Row, 0 to 99 from the near end to the far end
+    pub y: u8,
+}
+
+impl CourtCoordinate {
+    pub fn new(x: u8, y: u8) -> Option<CourtCoordinate> {
+        if x > 99 || y > 99 {
+            return None;
+        }
+
+        Some(CourtCoordinate { x, y })
+    }
+
+    // `0431` is row 4, column 31. Missing coordinates are written `-1-1` and give None.
+    pub fn parse(text: &str) -> Option<CourtCoordinate> {
+        let text = text.trim();
+
+        if text.len() != 4 || !text.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+
+        CourtCoordinate::new(text[2..].parse().ok()?, text[..2].parse().ok()?)
+    }
+
+    pub fn half(&self) -> CourtHalf {
+        if (self.y as f64) < NET_ROW {
+            CourtHalf::Near
+        } else {
+            CourtHalf::Far
+        }
+    }
+
+    // Metres from the centre line, positive to the right as seen from the near half
+    pub fn x_metres(&self) -> f64 {
+        (self.x as f64 - CENTRE_COLUMN) * COLUMN_METRES
+    }
+
+    // Metres from the net, negative on the near half
+    pub fn y_metres(&self) -> f64 {
+        (self.y as f64 - NET_ROW) * ROW_METRES
+    }
+
+    pub fn distance_from_net(&self) -> f64 {
+        self.y_metres().abs()
+    }
+
+    // Metres to the right of the centre line for the team defending this half
+    fn lateral_metres(&self) -> f64 {
+        match self.half() {
+            CourtHalf::Near => self.x_metres(),
+            CourtHalf::Far => -self.x_metres(),
+        }
+    }
+
+    // Zone 1 to 9 from the point of view of the team defending this half. Front and middle rows
+    // are 3 m deep, the back row and the side columns extend into the free zone.
+    pub fn zone(&self) -> u8 {
+        let depth = self.distance_from_net();
+        let lateral = self.lateral_metres();
+
+        let column = if lateral > 1.5 {
+            0
+        } else if lateral >= -1.5 {
+            1
+        } else {
+            2
+        };
+
+        let zones = if depth < 3.0 {
+            [2, 3, 4]
+        } else if depth < 6.0 {
+            [9, 8, 7]
+        } else {
+            [1, 6, 5]
+        };
+
+        zones[column]
+    }
+
+    // Quarter of the zone from the point of view of the team defending this half: A back right,
+    // B front right, C front left and D back left, as in the subzones Data Volley writes next to
+    // the coordinates. Points on a split count as back and left.
+    pub fn subzone(&self) -> char {
+        let depth = self.distance_from_net();
+        let lateral = self.lateral_metres();
+
+        let depth_split = if depth < 3.0 {
+            1.5
+        } else if depth < 6.0 {
+            4.5
+        } else {
+            (6.0 + HALF_LENGTH) / 2.0
+        };
+
+        let lateral_split = if lateral > 1.5 {
+            (1.5 + HALF_WIDTH) / 2.0
+        } else if lateral >= -1.5 {
+            0.0
+        } else {
+            -(1.5 + HALF_WIDTH) / 2.0
+        };
+
+        match (depth >= depth_split, lateral > lateral_split) {
+            (true, true) => 'A',
+            (false, true) => 'B',
+            (false, false) => 'C',
+            (true, false) => 'D',
+        }
+    }
+}
+
+impl fmt::Display for CourtCoordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}{:02}", self.y, self.x)
+    }
+}
@@ -1,15 +1,24 @@
+use std::collections::HashMap;
+use std::fmt;
 use std::io::{BufRead, Cursor, Read};
+use std::str::FromStr;
+use std::time::Duration;
 
-#[derive(Debug)]
+use crate::court::CourtCoordinate;
+use crate::datetime::{parse_video_time, Date, DateOrder, DateTime, Time};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Metadata {
     pub file_format: String,
     pub creation_data: ReleaseData,
     pub modification_data: ReleaseData,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReleaseData {
-    pub datetime: String,
+    pub datetime: Option<DateTime>,
     pub idp: String, // TODO: What does IDP stand for?
     pub program: String,
     pub version: String,
@@ -17,15 +26,19 @@ pub struct ReleaseData {
     pub scouter_name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Game {
-    pub date: String,
-    pub time: String,
+    pub date: Option<Date>,
+    pub time: Option<Time>,
     pub season: String,
+    // League or tournament, empty when the scout left it out
+    pub competition: String,
     pub game_type: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Team {
     pub team_id: String,
     pub team_name: String,
@@ -34,13 +47,15 @@ pub struct Team {
     pub assistant_coaches: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetPoints {
     pub home: u8,
     pub visiting: u8,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Set {
     pub set_number: u8,
     pub first_quarter: SetPoints,
@@ -50,7 +65,8 @@ pub struct Set {
     pub duration: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Player {
     pub team_id: String,
     pub player_number: u8,
@@ -59,40 +75,119 @@ pub struct Player {
     pub name: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Action {
     pub code: String,
     pub code_explanation: CodeExplanation,
     pub point_phase: String,
     pub attack_phase: String,
-    pub start_coordinate: String,
-    pub mid_coordinate: String,
-    pub end_coordinate: String,
-    pub time: String,
+    pub start_coordinate: Option<CourtCoordinate>,
+    pub mid_coordinate: Option<CourtCoordinate>,
+    pub end_coordinate: Option<CourtCoordinate>,
+    pub time: Option<Time>,
     pub set: u8,
     pub home_rotation: u8,
     pub visiting_rotation: u8,
     pub video_file_number: u8,
-    pub video_time: String,
+    pub video_time: Option<Duration>,
+    // Shirt numbers on court in positions 1 to 6, empty when the row carries no lineup
+    pub home_lineup: Vec<u8>,
+    pub visiting_lineup: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseCodeError {
+    InvalidTeamSide(char),
+    InvalidSkill(char),
+    InvalidActionType(char),
+    InvalidEvaluation(char),
+    // A string that is neither a single scout character nor a known name
+    UnknownName(String),
+}
+
+impl fmt::Display for ParseCodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseCodeError::InvalidTeamSide(c) => write!(f, "invalid team character '{}'", c),
+            ParseCodeError::InvalidSkill(c) => write!(f, "invalid skill character '{}'", c),
+            ParseCodeError::InvalidActionType(c) => {
+                write!(f, "invalid action type character '{}'", c)
+            }
+            ParseCodeError::InvalidEvaluation(c) => {
+                write!(f, "invalid evaluation character '{}'", c)
+            }
+            ParseCodeError::UnknownName(name) => write!(f, "unknown name '{}'", name),
+        }
+    }
+}
+
+impl std::error::Error for ParseCodeError {}
+
+// Shared by the `FromStr` implementations, accepts the scout character or the name in any case
+fn parse_char_or_name<T: Copy>(
+    s: &str,
+    all: &[T],
+    from_char: impl Fn(char) -> Result<T, ParseCodeError>,
+    name: impl Fn(&T) -> &'static str,
+) -> Result<T, ParseCodeError> {
+    let s = s.trim();
+    let mut chars = s.chars();
+
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return from_char(c);
+    }
+
+    all.iter()
+        .find(|value| name(value).eq_ignore_ascii_case(s))
+        .copied()
+        .ok_or_else(|| ParseCodeError::UnknownName(s.to_string()))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum TeamSide {
     Home,
     Visiting,
 }
 
 impl TeamSide {
-    pub fn from_char(c: char) -> TeamSide {
+    pub const ALL: [TeamSide; 2] = [TeamSide::Home, TeamSide::Visiting];
+
+    pub fn from_char(c: char) -> Result<TeamSide, ParseCodeError> {
         match c {
-            '*' => TeamSide::Home,
-            'a' => TeamSide::Visiting,
-            _ => TeamSide::Home, // TODO: Return an error instead of a default value
+            '*' => Ok(TeamSide::Home),
+            'a' => Ok(TeamSide::Visiting),
+            _ => Err(ParseCodeError::InvalidTeamSide(c)),
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            TeamSide::Home => '*',
+            TeamSide::Visiting => 'a',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TeamSide::Home => "Home",
+            TeamSide::Visiting => "Visiting",
+        }
+    }
+
+    pub fn opponent(&self) -> TeamSide {
+        match self {
+            TeamSide::Home => TeamSide::Visiting,
+            TeamSide::Visiting => TeamSide::Home,
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Skill {
     Serve,
     Reception,
@@ -103,7 +198,9 @@ pub enum Skill {
     FreeBall,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum ActionType {
     High,
     Medium,
@@ -115,7 +212,9 @@ pub enum ActionType {
 }
 
 // See page 30 of the Data Volley Scout manual for more information on evaluations per action type
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum Evaluation {
     Equal,
     Slash,
@@ -125,8 +224,378 @@ pub enum Evaluation {
     Hashtag,
 }
 
+impl Skill {
+    pub const ALL: [Skill; 7] = [
+        Skill::Serve,
+        Skill::Reception,
+        Skill::Attack,
+        Skill::Block,
+        Skill::Dig,
+        Skill::Set,
+        Skill::FreeBall,
+    ];
+
+    pub fn from_char(c: char) -> Result<Skill, ParseCodeError> {
+        match c {
+            'S' => Ok(Skill::Serve),
+            'R' => Ok(Skill::Reception),
+            'A' => Ok(Skill::Attack),
+            'B' => Ok(Skill::Block),
+            'D' => Ok(Skill::Dig),
+            'E' => Ok(Skill::Set),
+            'F' => Ok(Skill::FreeBall),
+            _ => Err(ParseCodeError::InvalidSkill(c)),
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            Skill::Serve => 'S',
+            Skill::Reception => 'R',
+            Skill::Attack => 'A',
+            Skill::Block => 'B',
+            Skill::Dig => 'D',
+            Skill::Set => 'E',
+            Skill::FreeBall => 'F',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Skill::Serve => "Serve",
+            Skill::Reception => "Reception",
+            Skill::Attack => "Attack",
+            Skill::Block => "Block",
+            Skill::Dig => "Dig",
+            Skill::Set => "Set",
+            Skill::FreeBall => "Free ball",
+        }
+    }
+
+    // What an evaluation means for this skill, following the evaluation tables of the manual
+    pub fn evaluation_description(&self, evaluation: Evaluation) -> &'static str {
+        use Evaluation::*;
+
+        match (self, evaluation) {
+            (_, Equal) => "Error",
+            (Skill::Serve, Slash) => "Positive, no attack",
+            (Skill::Serve, Minus) => "Negative, opponent free attack",
+            (Skill::Serve, Exclamation) => "OK, no first tempo possible",
+            (Skill::Serve, Plus) => "Positive, opponent some attack",
+            (Skill::Serve, Hashtag) => "Ace",
+            (Skill::Reception, Slash) => "Poor, no attack",
+            (Skill::Reception, Minus) => "Negative, limited attack",
+            (Skill::Reception, Exclamation) => "OK, no first tempo possible",
+            (Skill::Reception, Plus) => "Positive, attack",
+            (Skill::Reception, Hashtag) => "Perfect pass",
+            (Skill::Attack, Slash) => "Blocked",
+            (Skill::Attack, Minus) => "Poor, easily dug",
+            (Skill::Attack, Exclamation) => "Blocked for reattack",
+            (Skill::Attack, Plus) => "Positive, good attack",
+            (Skill::Attack, Hashtag) => "Kill",
+            (Skill::Block, Slash) => "Invasion",
+            (Skill::Block, Minus) => "Poor, opposition to replay",
+            (Skill::Block, Exclamation) => "Poor block",
+            (Skill::Block, Plus) => "Positive, block touch",
+            (Skill::Block, Hashtag) => "Winning block",
+            (Skill::Dig, Slash) => "Ball directly back over net",
+            (Skill::Dig, Minus) => "No structured attack possible",
+            (Skill::Dig, Exclamation) => "OK, no first tempo possible",
+            (Skill::Dig, Plus) => "Good dig",
+            (Skill::Dig, Hashtag) => "Perfect dig",
+            (Skill::Set, Slash) => "Poor, no attack possible",
+            (Skill::Set, Minus) => "Negative",
+            (Skill::Set, Exclamation) => "OK",
+            (Skill::Set, Plus) => "Positive",
+            (Skill::Set, Hashtag) => "Perfect",
+            (Skill::FreeBall, Slash) => "Poor",
+            (Skill::FreeBall, Minus) => "OK, only high set possible",
+            (Skill::FreeBall, Exclamation) => "OK, no first tempo possible",
+            (Skill::FreeBall, Plus) => "Good",
+            (Skill::FreeBall, Hashtag) => "Perfect",
+        }
+    }
+}
+
+impl ActionType {
+    pub const ALL: [ActionType; 7] = [
+        ActionType::High,
+        ActionType::Medium,
+        ActionType::Quick,
+        ActionType::Tense,
+        ActionType::Super,
+        ActionType::Fast,
+        ActionType::Other,
+    ];
+
+    pub fn from_char(c: char) -> Result<ActionType, ParseCodeError> {
+        match c {
+            'H' => Ok(ActionType::High),
+            'M' => Ok(ActionType::Medium),
+            'Q' => Ok(ActionType::Quick),
+            'T' => Ok(ActionType::Tense),
+            // S and F are kept for scouts that write the names' initials
+            'U' | 'S' => Ok(ActionType::Super),
+            'N' | 'F' => Ok(ActionType::Fast),
+            'O' => Ok(ActionType::Other),
+            _ => Err(ParseCodeError::InvalidActionType(c)),
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            ActionType::High => 'H',
+            ActionType::Medium => 'M',
+            ActionType::Quick => 'Q',
+            ActionType::Tense => 'T',
+            ActionType::Super => 'U',
+            ActionType::Fast => 'N',
+            ActionType::Other => 'O',
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ActionType::High => "High",
+            ActionType::Medium => "Medium",
+            ActionType::Quick => "Quick",
+            ActionType::Tense => "Tense",
+            ActionType::Super => "Super",
+            ActionType::Fast => "Fast",
+            ActionType::Other => "Other",
+        }
+    }
+}
+
+// Serve style carried by the action type of a serve code, e.g. `SM` for a jump float serve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ServeStyle {
+    Float,
+    JumpFloat,
+    Jump,
+    Topspin,
+    Hybrid,
+    Other,
+}
+
+impl ServeStyle {
+    pub const ALL: [ServeStyle; 6] = [
+        ServeStyle::Float,
+        ServeStyle::JumpFloat,
+        ServeStyle::Jump,
+        ServeStyle::Topspin,
+        ServeStyle::Hybrid,
+        ServeStyle::Other,
+    ];
+
+    // H float, M jump float, Q jump, T topspin, N hybrid, anything else other
+    pub fn from_action_type(action_type: ActionType) -> ServeStyle {
+        match action_type {
+            ActionType::High => ServeStyle::Float,
+            ActionType::Medium => ServeStyle::JumpFloat,
+            ActionType::Quick => ServeStyle::Jump,
+            ActionType::Tense => ServeStyle::Topspin,
+            ActionType::Fast => ServeStyle::Hybrid,
+            ActionType::Super | ActionType::Other => ServeStyle::Other,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ServeStyle::Float => "Float",
+            ServeStyle::JumpFloat => "Jump float",
+            ServeStyle::Jump => "Jump",
+            ServeStyle::Topspin => "Topspin",
+            ServeStyle::Hybrid => "Hybrid",
+            ServeStyle::Other => "Other",
+        }
+    }
+}
+
+// Tempo of a set or attack, also scouted on blocks, digs and free balls for the attack they answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Tempo {
+    High,
+    Half,
+    Quick,
+    Tense,
+    Super,
+    Fast,
+    Other,
+}
+
+impl Tempo {
+    pub fn from_action_type(action_type: ActionType) -> Tempo {
+        match action_type {
+            ActionType::High => Tempo::High,
+            ActionType::Medium => Tempo::Half,
+            ActionType::Quick => Tempo::Quick,
+            ActionType::Tense => Tempo::Tense,
+            ActionType::Super => Tempo::Super,
+            ActionType::Fast => Tempo::Fast,
+            ActionType::Other => Tempo::Other,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tempo::High => "High",
+            Tempo::Half => "Half",
+            Tempo::Quick => "Quick",
+            Tempo::Tense => "Tense",
+            Tempo::Super => "Super",
+            Tempo::Fast => "Fast",
+            Tempo::Other => "Other",
+        }
+    }
+}
+
+// What the action type of a code means for its skill
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SkillActionType {
+    Serve(ServeStyle),
+    // Style of the serve received
+    Reception(ServeStyle),
+    Set(Tempo),
+    Attack(Tempo),
+    // Tempo of the attack blocked, dug or sent back as a free ball
+    Block(Tempo),
+    Dig(Tempo),
+    FreeBall(Tempo),
+}
+
+impl SkillActionType {
+    pub fn new(skill: Skill, action_type: ActionType) -> SkillActionType {
+        let style = ServeStyle::from_action_type(action_type);
+        let tempo = Tempo::from_action_type(action_type);
+
+        match skill {
+            Skill::Serve => SkillActionType::Serve(style),
+            Skill::Reception => SkillActionType::Reception(style),
+            Skill::Set => SkillActionType::Set(tempo),
+            Skill::Attack => SkillActionType::Attack(tempo),
+            Skill::Block => SkillActionType::Block(tempo),
+            Skill::Dig => SkillActionType::Dig(tempo),
+            Skill::FreeBall => SkillActionType::FreeBall(tempo),
+        }
+    }
+
+    // E.g. "Jump float serve", "Quick attack" or "Dig of a high attack"
+    pub fn description(&self) -> String {
+        match self {
+            SkillActionType::Serve(style) => format!("{} serve", style.name()),
+            SkillActionType::Reception(style) => {
+                format!("Reception of a {} serve", style.name().to_lowercase())
+            }
+            SkillActionType::Set(tempo) => format!("{} set", tempo.name()),
+            SkillActionType::Attack(tempo) => format!("{} attack", tempo.name()),
+            SkillActionType::Block(tempo) => {
+                format!("Block on a {} attack", tempo.name().to_lowercase())
+            }
+            SkillActionType::Dig(tempo) => {
+                format!("Dig of a {} attack", tempo.name().to_lowercase())
+            }
+            SkillActionType::FreeBall(tempo) => {
+                format!("Free ball after a {} attack", tempo.name().to_lowercase())
+            }
+        }
+    }
+}
+
+impl Evaluation {
+    pub const ALL: [Evaluation; 6] = [
+        Evaluation::Equal,
+        Evaluation::Slash,
+        Evaluation::Minus,
+        Evaluation::Exclamation,
+        Evaluation::Plus,
+        Evaluation::Hashtag,
+    ];
+
+    pub fn from_char(c: char) -> Result<Evaluation, ParseCodeError> {
+        match c {
+            '=' => Ok(Evaluation::Equal),
+            '/' => Ok(Evaluation::Slash),
+            '-' => Ok(Evaluation::Minus),
+            '!' => Ok(Evaluation::Exclamation),
+            '+' => Ok(Evaluation::Plus),
+            '#' => Ok(Evaluation::Hashtag),
+            _ => Err(ParseCodeError::InvalidEvaluation(c)),
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            Evaluation::Equal => '=',
+            Evaluation::Slash => '/',
+            Evaluation::Minus => '-',
+            Evaluation::Exclamation => '!',
+            Evaluation::Plus => '+',
+            Evaluation::Hashtag => '#',
+        }
+    }
+
+    // The general meaning of the evaluation, see `Skill::evaluation_description` for the
+    // meaning within a skill
+    pub fn name(&self) -> &'static str {
+        match self {
+            Evaluation::Equal => "Error",
+            Evaluation::Slash => "Poor",
+            Evaluation::Minus => "Negative",
+            Evaluation::Exclamation => "OK",
+            Evaluation::Plus => "Positive",
+            Evaluation::Hashtag => "Perfect",
+        }
+    }
+}
+
+macro_rules! char_conversions {
+    ($type:ty) => {
+        impl fmt::Display for $type {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.name())
+            }
+        }
+
+        impl FromStr for $type {
+            type Err = ParseCodeError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                parse_char_or_name(s, &<$type>::ALL, <$type>::from_char, <$type>::name)
+            }
+        }
+
+        impl TryFrom<char> for $type {
+            type Error = ParseCodeError;
+
+            fn try_from(c: char) -> Result<Self, Self::Error> {
+                <$type>::from_char(c)
+            }
+        }
+
+        impl From<$type> for char {
+            fn from(value: $type) -> char {
+                value.to_char()
+            }
+        }
+    };
+}
+
+char_conversions!(TeamSide);
+char_conversions!(Skill);
+char_conversions!(ActionType);
+char_conversions!(Evaluation);
+
 // See page 41 of the Data Volley Scout manual.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum CodeExplanation {
     InvalidCode,
     ManualCode(ManualCodeExplanation),
@@ -136,56 +605,123 @@ pub enum CodeExplanation {
 }
 
 // From page 27 of the Data Volley Scout manual (https://dataprojectwebsoftware.blob.core.windows.net/software/dvw4media/DataVolleyMedia_handbook.pdf)
-// Only parses the main code, not the advanced and extended codes
-#[derive(Debug)]
+// The advanced and extended code follow the main code, `~` marks a missing value
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ManualCodeExplanation {
     pub team: TeamSide,
     pub player_number: u8,
     pub skill: Skill,
     pub action_type: ActionType,
     pub evaluation: Evaluation,
+    // Attack combination for attacks (`X5`), setter call for sets (`K1`)
+    pub combination: Option<String>,
+    // F (front), B (back), C (center), P (pipe) or S (setter)
+    pub target_attack: Option<char>,
+    pub start_zone: Option<u8>,
+    pub end_zone: Option<u8>,
+    // A to D within the end zone
+    pub end_subzone: Option<char>,
+    // Skill specific, e.g. the attack hit type (H, P, T) or the reception side (L, R, M, W, O)
+    pub skill_type: Option<char>,
+    // Skill specific, e.g. the number of blockers for an attack
+    pub players: Option<u8>,
+    pub special: Option<char>,
+    // Everything after the extended code, left to the scout
+    pub custom: String,
 }
 
-#[derive(Debug)]
+// A row of [3ATTACKCOMBINATION], e.g. `X5;4;R;T;Go;;16711680;4912;F;;`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttackCombination {
+    pub code: String,
+    pub start_zone: Option<u8>,
+    // L, R or C
+    pub side: Option<char>,
+    // Ball type as in the attack code, e.g. H, Q, T, N or U
+    pub ball_type: Option<char>,
+    pub description: String,
+    pub coordinate: Option<CourtCoordinate>,
+    pub target_attack: Option<char>,
+}
+
+// A row of [3SETTERCALL], e.g. `K1;;Quick ahead;;16711680;3949;4549;4949;;12632256;`
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetterCall {
+    pub code: String,
+    pub description: String,
+    // Start, middle and end of the run of the quick attacker drawn for the call, empty for a call
+    // without a quick
+    pub quick_run: Vec<CourtCoordinate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GreenCodePoint {
     pub team: TeamSide,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetterPosition {
     pub team: TeamSide,
     pub setter_position: u8,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point {
     pub team: TeamSide,
     pub home_score: u8,
     pub visiting_score: u8,
 }
 
-#[derive(Debug)]
+// `*T` or `aT`, a timeout called by the team
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timeout {
+    pub team: TeamSide,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SetterReplacement {
     pub team: TeamSide,
     pub setter_number: u8,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Substitution {
     pub team: TeamSide,
     pub player_out: u8,
     pub player_in: u8,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
 pub enum AutomaticCodeExplanation {
     SetterPosition(SetterPosition),
     Point(Point),
     SetterReplacement(SetterReplacement),
     Substitution(Substitution),
+    Timeout(Timeout),
 }
 
-#[derive(Debug)]
+// A code that references a shirt number which is not on the team's roster
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UnknownPlayerReference {
+    pub action_index: usize,
+    pub team: TeamSide,
+    pub player_number: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ScoutFile {
     pub metadata: Metadata,
     pub game: Game,
@@ -194,6 +730,8 @@ pub struct ScoutFile {
     pub sets: Vec<Set>,
     pub home_players: Vec<Player>,
     pub visiting_players: Vec<Player>,
+    pub attack_combinations: Vec<AttackCombination>,
+    pub setter_calls: Vec<SetterCall>,
     pub actions: Vec<Action>,
 }
 
@@ -213,7 +751,7 @@ impl Metadata {
 
 impl ReleaseData {
     pub fn new(
-        datetime: String,
+        datetime: Option<DateTime>,
         idp: String,
         program: String,
         version: String,
@@ -232,11 +770,18 @@ impl ReleaseData {
 }
 
 impl Game {
-    pub fn new(date: String, time: String, season: String, game_type: String) -> Game {
+    pub fn new(
+        date: Option<Date>,
+        time: Option<Time>,
+        season: String,
+        competition: String,
+        game_type: String,
+    ) -> Game {
         Game {
             date,
             time,
             season,
+            competition,
             game_type,
         }
     }
@@ -308,58 +853,70 @@ impl CodeExplanation {
     pub fn new(code: String) -> CodeExplanation {
         let code = code.trim().chars().collect::<Vec<char>>();
 
-        if code.len() < 3 {
+        if code.len() < 2 {
             return CodeExplanation::InvalidCode;
         }
 
-        let team = TeamSide::from_char(code[0]);
+        let team = match TeamSide::from_char(code[0]) {
+            Ok(team) => team,
+            Err(_) => return CodeExplanation::InvalidCode,
+        };
+
+        if code.len() == 2 {
+            return match code[1] {
+                'T' => CodeExplanation::AutomaticCode(AutomaticCodeExplanation::Timeout(Timeout {
+                    team,
+                })),
+                _ => CodeExplanation::InvalidCode,
+            };
+        }
 
         let player_number: Result<i32, _> = code[1..3].iter().collect::<String>().parse();
 
-        if player_number.is_ok() {
-            let skill = match code[3] {
-                'S' => Skill::Serve,
-                'R' => Skill::Reception,
-                'A' => Skill::Attack,
-                'B' => Skill::Block,
-                'D' => Skill::Dig,
-                'E' => Skill::Set,
-                'F' => Skill::FreeBall,
-                _ => Skill::FreeBall, // TODO: Return an error instead of a default value
-            };
+        if let Ok(player_number) = player_number {
+            if code.len() < 6 {
+                return CodeExplanation::InvalidCode;
+            }
 
-            let action_type = match code[4] {
-                'H' => ActionType::High,
-                'M' => ActionType::Medium,
-                'Q' => ActionType::Quick,
-                'T' => ActionType::Tense,
-                'S' => ActionType::Super,
-                'N' => ActionType::Fast,
-                'O' => ActionType::Other,
-                _ => ActionType::Other, // TODO: Return an error instead of a default value
-            };
+            // An unknown ball type does not make the touch unusable
+            let action_type = ActionType::from_char(code[4]).unwrap_or(ActionType::Other);
 
-            let evaluation = match code[5] {
-                '=' => Evaluation::Equal,
-                '/' => Evaluation::Slash,
-                '-' => Evaluation::Minus,
-                '!' => Evaluation::Exclamation,
-                '+' => Evaluation::Plus,
-                '#' => Evaluation::Hashtag,
-                _ => Evaluation::Equal, // TODO: Return an error instead of a default value
-            };
+            match (Skill::from_char(code[3]), Evaluation::from_char(code[5])) {
+                (Ok(skill), Ok(evaluation)) => {
+                    let advanced = |index: usize| code.get(index).copied().filter(|c| *c != '~');
+                    let digit = |index: usize| {
+                        advanced(index)
+                            .and_then(|c| c.to_digit(10))
+                            .map(|n| n as u8)
+                    };
 
-            CodeExplanation::ManualCode(ManualCodeExplanation {
-                team,
-                player_number: player_number.unwrap() as u8,
-                skill,
-                action_type,
-                evaluation,
-            })
+                    let combination = match (advanced(6), advanced(7)) {
+                        (Some(first), Some(second)) => Some(format!("{}{}", first, second)),
+                        _ => None,
+                    };
+
+                    CodeExplanation::ManualCode(ManualCodeExplanation {
+                        team,
+                        player_number: player_number as u8,
+                        skill,
+                        action_type,
+                        evaluation,
+                        combination,
+                        target_attack: advanced(8),
+                        start_zone: digit(9),
+                        end_zone: digit(10),
+                        end_subzone: advanced(11),
+                        skill_type: advanced(12),
+                        players: digit(13),
+                        special: advanced(14),
+                        custom: code.iter().skip(15).collect(),
+                    })
+                }
+                _ => CodeExplanation::InvalidCode,
+            }
         } else {
             match code[1] {
                 'z' => {
-                    let team = TeamSide::from_char(code[0]);
                     let n = code[2].to_digit(10);
 
                     match n {
@@ -377,7 +934,6 @@ impl CodeExplanation {
                         return CodeExplanation::InvalidCode;
                     }
 
-                    let team = TeamSide::from_char(code[0]);
                     let home_score = code[2..4].iter().collect::<String>().parse();
                     let visiting_score = code[5..7].iter().collect::<String>().parse();
 
@@ -393,8 +949,6 @@ impl CodeExplanation {
                     }
                 }
                 'P' => {
-                    let team = TeamSide::from_char(code[0]);
-
                     let setter_number = if code.len() < 4 {
                         code[2].to_string().parse()
                     } else {
@@ -412,7 +966,6 @@ impl CodeExplanation {
                     }
                 }
                 'c' => {
-                    let team = TeamSide::from_char(code[0]);
                     let player_out = code[2..4].iter().collect::<String>().parse();
                     let player_in = code[5..7].iter().collect::<String>().parse();
 
@@ -443,22 +996,117 @@ impl CodeExplanation {
             }
         }
     }
+
+    pub fn team(&self) -> Option<TeamSide> {
+        match self {
+            CodeExplanation::ManualCode(code) => Some(code.team),
+            CodeExplanation::GreenCode(code) => Some(code.team),
+            CodeExplanation::AutomaticCode(code) => Some(match code {
+                AutomaticCodeExplanation::SetterPosition(code) => code.team,
+                AutomaticCodeExplanation::Point(code) => code.team,
+                AutomaticCodeExplanation::SetterReplacement(code) => code.team,
+                AutomaticCodeExplanation::Substitution(code) => code.team,
+                AutomaticCodeExplanation::Timeout(code) => code.team,
+            }),
+            CodeExplanation::InvalidCode | CodeExplanation::SetEnd(_) => None,
+        }
+    }
+
+    // Every (team, shirt number) pair the code refers to, a substitution refers to both players
+    pub fn player_references(&self) -> Vec<(TeamSide, u8)> {
+        match self {
+            CodeExplanation::ManualCode(code) => vec![(code.team, code.player_number)],
+            CodeExplanation::AutomaticCode(AutomaticCodeExplanation::SetterReplacement(code)) => {
+                vec![(code.team, code.setter_number)]
+            }
+            CodeExplanation::AutomaticCode(AutomaticCodeExplanation::Substitution(code)) => {
+                vec![(code.team, code.player_out), (code.team, code.player_in)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl ManualCodeExplanation {
+    pub fn attack_combination(&self) -> Option<&str> {
+        match self.skill {
+            Skill::Attack => self.combination.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn setter_call(&self) -> Option<&str> {
+        match self.skill {
+            Skill::Set => self.combination.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn serve_style(&self) -> Option<ServeStyle> {
+        match self.skill {
+            Skill::Serve => Some(ServeStyle::from_action_type(self.action_type)),
+            _ => None,
+        }
+    }
+
+    pub fn skill_action_type(&self) -> SkillActionType {
+        SkillActionType::new(self.skill, self.action_type)
+    }
+
+    // Tempo of sets and attacks
+    pub fn tempo(&self) -> Option<Tempo> {
+        match self.skill_action_type() {
+            SkillActionType::Set(tempo) | SkillActionType::Attack(tempo) => Some(tempo),
+            _ => None,
+        }
+    }
+
+    pub fn action_type_description(&self) -> String {
+        self.skill_action_type().description()
+    }
+
+    // Number of blockers facing an attack, 4 is scouted for a hole in the block
+    pub fn blockers(&self) -> Option<u8> {
+        match self.skill {
+            Skill::Attack => self.players,
+            _ => None,
+        }
+    }
+
+    pub fn evaluation_description(&self) -> &'static str {
+        self.skill.evaluation_description(self.evaluation)
+    }
+
+    // The main code as it is written in the scout, e.g. `*19SM+`
+    pub fn main_code(&self) -> String {
+        format!(
+            "{}{:02}{}{}{}",
+            self.team.to_char(),
+            self.player_number,
+            self.skill.to_char(),
+            self.action_type.to_char(),
+            self.evaluation.to_char()
+        )
+    }
 }
 
 impl Action {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         code: String,
         point_phase: String,
         attack_phase: String,
-        start_coordinate: String,
-        mid_coordinate: String,
-        end_coordinate: String,
-        time: String,
+        start_coordinate: Option<CourtCoordinate>,
+        mid_coordinate: Option<CourtCoordinate>,
+        end_coordinate: Option<CourtCoordinate>,
+        time: Option<Time>,
         set: u8,
         home_rotation: u8,
         visiting_rotation: u8,
         video_file_number: u8,
-        video_time: String,
+        video_time: Option<Duration>,
+        home_lineup: Vec<u8>,
+        visiting_lineup: Vec<u8>,
     ) -> Action {
         Action {
             code: code.clone(),
@@ -474,11 +1122,14 @@ impl Action {
             visiting_rotation,
             video_file_number,
             video_time,
+            home_lineup,
+            visiting_lineup,
         }
     }
 }
 
 impl ScoutFile {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         metadata: Metadata,
         game: Game,
@@ -487,6 +1138,8 @@ impl ScoutFile {
         sets: Vec<Set>,
         home_players: Vec<Player>,
         visiting_players: Vec<Player>,
+        attack_combinations: Vec<AttackCombination>,
+        setter_calls: Vec<SetterCall>,
         actions: Vec<Action>,
     ) -> ScoutFile {
         ScoutFile {
@@ -497,19 +1150,123 @@ impl ScoutFile {
             sets,
             home_players,
             visiting_players,
+            attack_combinations,
+            setter_calls,
             actions,
         }
     }
+
+    pub fn attack_combination(&self, code: &str) -> Option<&AttackCombination> {
+        self.attack_combinations
+            .iter()
+            .find(|combination| combination.code == code)
+    }
+
+    pub fn setter_call(&self, code: &str) -> Option<&SetterCall> {
+        self.setter_calls.iter().find(|call| call.code == code)
+    }
+
+    pub fn team(&self, side: TeamSide) -> &Team {
+        match side {
+            TeamSide::Home => &self.home_team,
+            TeamSide::Visiting => &self.visiting_team,
+        }
+    }
+
+    // The side `team_id` played on, None when the team did not play the match
+    pub fn side_of(&self, team_id: &str) -> Option<TeamSide> {
+        TeamSide::ALL
+            .into_iter()
+            .find(|&side| self.team(side).team_id == team_id)
+    }
+
+    pub fn players(&self, side: TeamSide) -> &[Player] {
+        match side {
+            TeamSide::Home => &self.home_players,
+            TeamSide::Visiting => &self.visiting_players,
+        }
+    }
+
+    // Roster of one team keyed by shirt number
+    pub fn roster(&self, side: TeamSide) -> HashMap<u8, &Player> {
+        self.players(side)
+            .iter()
+            .map(|player| (player.player_number, player))
+            .collect()
+    }
+
+    pub fn player(&self, side: TeamSide, player_number: u8) -> Option<&Player> {
+        self.players(side)
+            .iter()
+            .find(|player| player.player_number == player_number)
+    }
+
+    // The player performing the action. For a setter replacement this is the new setter,
+    // for a substitution the player coming in.
+    pub fn player_for(&self, action: &Action) -> Option<&Player> {
+        let (team, player_number) = action
+            .code_explanation
+            .player_references()
+            .last()
+            .copied()?;
+
+        self.player(team, player_number)
+    }
+
+    // Every manual code of the match in scouting order
+    pub fn touches(&self) -> impl Iterator<Item = &ManualCodeExplanation> {
+        self.actions
+            .iter()
+            .filter_map(|action| match &action.code_explanation {
+                CodeExplanation::ManualCode(code) => Some(code),
+                _ => None,
+            })
+    }
+
+    pub fn team_for(&self, action: &Action) -> Option<&Team> {
+        action.code_explanation.team().map(|team| self.team(team))
+    }
+
+    pub fn unknown_player_references(&self) -> Vec<UnknownPlayerReference> {
+        let home_roster = self.roster(TeamSide::Home);
+        let visiting_roster = self.roster(TeamSide::Visiting);
+
+        let mut unknown = Vec::new();
+
+        for (action_index, action) in self.actions.iter().enumerate() {
+            for (team, player_number) in action.code_explanation.player_references() {
+                let roster = match team {
+                    TeamSide::Home => &home_roster,
+                    TeamSide::Visiting => &visiting_roster,
+                };
+
+                if !roster.contains_key(&player_number) {
+                    unknown.push(UnknownPlayerReference {
+                        action_index,
+                        team,
+                        player_number,
+                    });
+                }
+            }
+        }
+
+        unknown
+    }
 }
 
-pub fn read_scout_file(input: impl Read) -> Result<ScoutFile, std::io::Error> {
-    let input = input.bytes().flatten().collect::<Vec<u8>>();
+pub fn read_scout_file(mut input: impl Read) -> Result<ScoutFile, std::io::Error> {
+    let mut bytes = Vec::new();
+    input.read_to_end(&mut bytes)?;
+    let input = bytes;
     let buffer = String::from_utf8_lossy(&input).to_string();
 
+    let header = buffer.split("[3SCOUT]").next().unwrap_or_default();
+    let date_order = DateOrder::detect(header);
+
     let mut reader = Cursor::new(buffer);
 
-    let metadata = read_metadata(&mut reader)?;
-    let game = read_game(&mut reader)?;
+    let metadata = read_metadata(&mut reader, date_order)?;
+    let game = read_game(&mut reader, date_order)?;
     let home_team = read_team(&mut reader, false)?;
     let visiting_team = read_team(&mut reader, true)?;
     // skip_category(&mut reader, "[3MORE]")?;
@@ -518,8 +1275,8 @@ pub fn read_scout_file(input: impl Read) -> Result<ScoutFile, std::io::Error> {
     let sets = read_sets(&mut reader)?;
     let home_players = read_players(&mut reader)?;
     let visiting_players = read_players(&mut reader)?;
-    // skip_category(&mut reader, "[3ATTACKCOMBINATION]")?;
-    // skip_category(&mut reader, "[3SETTERCALL]")?;
+    let attack_combinations = read_attack_combinations(&mut reader)?;
+    let setter_calls = read_setter_calls(&mut reader)?;
     // skip_category(&mut reader, "[3WINNINGSYMBOLS]")?;
     // skip_category(&mut reader, "[3RESERVE]")?;
     skip_until(&mut reader, "[3SCOUT]")?;
@@ -533,6 +1290,8 @@ pub fn read_scout_file(input: impl Read) -> Result<ScoutFile, std::io::Error> {
         sets,
         home_players,
         visiting_players,
+        attack_combinations,
+        setter_calls,
         actions,
     })
 }
@@ -540,7 +1299,7 @@ pub fn read_scout_file(input: impl Read) -> Result<ScoutFile, std::io::Error> {
 macro_rules! metadata_field {
     ($field:expr) => {
         $field
-            .split(":")
+            .splitn(2, ":")
             .last()
             .unwrap_or("ERROR PARSING")
             .to_string()
@@ -549,7 +1308,10 @@ macro_rules! metadata_field {
     };
 }
 
-pub fn read_metadata(reader: &mut Cursor<String>) -> Result<Metadata, std::io::Error> {
+pub fn read_metadata(
+    reader: &mut Cursor<String>,
+    date_order: DateOrder,
+) -> Result<Metadata, std::io::Error> {
     let mut header = String::new();
     reader.read_line(&mut header)?;
 
@@ -564,17 +1326,20 @@ pub fn read_metadata(reader: &mut Cursor<String>) -> Result<Metadata, std::io::E
     reader.read_line(&mut file_format)?;
     file_format = metadata_field!(file_format);
 
-    let creation_data = read_release_data(reader)?;
+    let creation_data = read_release_data(reader, date_order)?;
 
-    let modification_data = read_release_data(reader)?;
+    let modification_data = read_release_data(reader, date_order)?;
 
     Ok(Metadata::new(file_format, creation_data, modification_data))
 }
 
-pub fn read_release_data(reader: &mut Cursor<String>) -> Result<ReleaseData, std::io::Error> {
+pub fn read_release_data(
+    reader: &mut Cursor<String>,
+    date_order: DateOrder,
+) -> Result<ReleaseData, std::io::Error> {
     let mut datetime = String::new();
     reader.read_line(&mut datetime)?;
-    datetime = metadata_field!(datetime);
+    let datetime = DateTime::parse(&metadata_field!(datetime), date_order);
 
     let mut idp = String::new();
     reader.read_line(&mut idp)?;
@@ -606,7 +1371,10 @@ pub fn read_release_data(reader: &mut Cursor<String>) -> Result<ReleaseData, std
     ))
 }
 
-pub fn read_game(reader: &mut Cursor<String>) -> Result<Game, std::io::Error> {
+pub fn read_game(
+    reader: &mut Cursor<String>,
+    date_order: DateOrder,
+) -> Result<Game, std::io::Error> {
     let mut header = String::new();
     reader.read_line(&mut header)?;
 
@@ -620,11 +1388,14 @@ pub fn read_game(reader: &mut Cursor<String>) -> Result<Game, std::io::Error> {
     let mut data = String::new();
     reader.read_line(&mut data)?;
 
-    let splitted = data.split(";").map(|s| s.trim().to_string()).collect::<Vec<String>>();
-    
-    let (date, time, season, game_type) = (
+    let splitted = data
+        .split(";")
+        .map(|s| s.trim().to_string())
+        .collect::<Vec<String>>();
+
+    let (date, time, season, competition, game_type) = (
         splitted
-            .get(0)
+            .first()
             .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid date"))?
             .trim()
             .to_string(),
@@ -638,6 +1409,7 @@ pub fn read_game(reader: &mut Cursor<String>) -> Result<Game, std::io::Error> {
             .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid season"))?
             .trim()
             .to_string(),
+        splitted.get(3).cloned().unwrap_or_default(),
         splitted
             .get(5)
             .ok_or_else(|| {
@@ -650,7 +1422,13 @@ pub fn read_game(reader: &mut Cursor<String>) -> Result<Game, std::io::Error> {
     // skip a line
     reader.read_line(&mut String::new())?;
 
-    Ok(Game::new(date, time, season, game_type))
+    Ok(Game::new(
+        Date::parse(&date, date_order),
+        Time::parse(&time),
+        season,
+        competition,
+        game_type,
+    ))
 }
 
 pub fn read_team(reader: &mut Cursor<String>, skip_header: bool) -> Result<Team, std::io::Error> {
@@ -673,7 +1451,7 @@ pub fn read_team(reader: &mut Cursor<String>, skip_header: bool) -> Result<Team,
 
     let (team_id, team_name, sets_won, head_coach, assistant_coaches) = (
         splitted
-            .get(0)
+            .first()
             .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "Invalid team id"))?
             .trim()
             .to_string(),
@@ -691,7 +1469,7 @@ pub fn read_team(reader: &mut Cursor<String>, skip_header: bool) -> Result<Team,
             })?
             .trim()
             .parse()
-            .unwrap_or_else(|_| 0),
+            .unwrap_or(0),
         splitted
             .get(3)
             .ok_or_else(|| {
@@ -808,7 +1586,7 @@ pub fn read_players(reader: &mut Cursor<String>) -> Result<Vec<Player>, std::io:
             .collect::<Vec<String>>();
 
         players.push(Player::new(
-            splitted.get(0).unwrap().into(),
+            splitted.first().unwrap().into(),
             splitted.get(1).unwrap().parse().unwrap(),
             splitted.get(8).unwrap().into(),
             splitted.get(9).unwrap().into(),
@@ -842,26 +1620,125 @@ pub fn read_actions(reader: &mut Cursor<String>) -> Result<Vec<Action>, std::io:
             .map(|s| s.trim().to_string())
             .collect::<Vec<String>>();
 
-        // TODO: Figure out what indexes all these fields are
+        let field = |index: usize| splitted.get(index).cloned().unwrap_or_default();
+        let number = |index: usize| field(index).parse().unwrap_or(0);
+        let lineup = |range: std::ops::Range<usize>| {
+            range
+                .filter_map(|index| field(index).parse().ok())
+                .collect::<Vec<u8>>()
+        };
+
+        // Index 3 and 13 are always empty
         actions.push(Action::new(
-            splitted.get(0).unwrap().into(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            String::new(),
-            0,
-            0,
-            0,
-            0,
-            String::new(),
+            field(0),
+            field(1),
+            field(2),
+            CourtCoordinate::parse(&field(4)),
+            CourtCoordinate::parse(&field(5)),
+            CourtCoordinate::parse(&field(6)),
+            Time::parse(&field(7)),
+            number(8),
+            number(9),
+            number(10),
+            number(11),
+            parse_video_time(&field(12)),
+            lineup(14..20),
+            lineup(20..26),
         ));
     }
 
     Ok(actions)
 }
 
+// Rows of an optional category, leaves the reader untouched when the category is missing
+fn read_optional_category(
+    reader: &mut Cursor<String>,
+    category: &str,
+) -> Result<Vec<Vec<String>>, std::io::Error> {
+    let start = reader.position();
+
+    loop {
+        let mut data = String::new();
+
+        if reader.read_line(&mut data)? == 0 || data.trim().starts_with("[3SCOUT]") {
+            reader.set_position(start);
+            return Ok(Vec::new());
+        }
+
+        if data.trim().starts_with(category) {
+            break;
+        }
+    }
+
+    let mut rows = Vec::new();
+
+    loop {
+        let mut data = String::new();
+
+        if reader.read_line(&mut data)? == 0 {
+            break;
+        }
+
+        if data.trim().starts_with("[") {
+            reader.set_position(reader.position() - data.len() as u64);
+            break;
+        }
+
+        rows.push(
+            data.split(";")
+                .map(|s| s.trim().to_string())
+                .collect::<Vec<String>>(),
+        );
+    }
+
+    Ok(rows)
+}
+
+pub fn read_attack_combinations(
+    reader: &mut Cursor<String>,
+) -> Result<Vec<AttackCombination>, std::io::Error> {
+    let rows = read_optional_category(reader, "[3ATTACKCOMBINATION]")?;
+
+    Ok(rows
+        .iter()
+        .filter(|row| !row[0].is_empty())
+        .map(|row| {
+            let field = |index: usize| row.get(index).cloned().unwrap_or_default();
+            let character = |index: usize| field(index).chars().next();
+
+            AttackCombination {
+                code: field(0),
+                start_zone: field(1).parse().ok(),
+                side: character(2),
+                ball_type: character(3),
+                description: field(4),
+                coordinate: CourtCoordinate::parse(&field(7)),
+                target_attack: character(8),
+            }
+        })
+        .collect())
+}
+
+pub fn read_setter_calls(reader: &mut Cursor<String>) -> Result<Vec<SetterCall>, std::io::Error> {
+    let rows = read_optional_category(reader, "[3SETTERCALL]")?;
+
+    Ok(rows
+        .iter()
+        .filter(|row| !row[0].is_empty())
+        .map(|row| SetterCall {
+            code: row[0].clone(),
+            description: row.get(2).cloned().unwrap_or_default(),
+            quick_run: row
+                .iter()
+                .skip(5)
+                .take(3)
+                .filter_map(|field| CourtCoordinate::parse(field))
+                .collect(),
+        })
+        .collect())
+}
+
+#[allow(dead_code)]
 fn skip_category(reader: &mut Cursor<String>, category: &str) -> Result<(), std::io::Error> {
     let mut header = String::new();
     reader.read_line(&mut header)?;
@@ -0,0 +1,210 @@
+use std::fmt;
+use std::time::Duration;
+
+// Generators write dates in the locale of the scouting computer, VolleyMetrics uses
+// `09/19/2020` (month first) while most European installations of Data Volley use `19/09/2020`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DateOrder {
+    DayFirst,
+    MonthFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Date {
+    pub year: u16,
+    pub month: u8,
+    pub day: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Time {
+    pub hour: u8,
+    pub minute: u8,
+    pub second: u8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DateTime {
+    pub date: Date,
+    pub time: Time,
+}
+
+impl DateOrder {
+    // Decides the order from every date in the text that has a day above 12. Falls back to
+    // day first, the Data Volley default, when none of them tell.
+    pub fn detect(text: &str) -> DateOrder {
+        let mut day_first = 0;
+        let mut month_first = 0;
+
+        for token in text.split(|c: char| c == ';' || c.is_whitespace()) {
+            let parts = split_date(token);
+
+            if parts.len() != 3 || parts[0].len() > 2 || parts[2].len() != 4 {
+                continue;
+            }
+
+            match (parts[0].parse::<u8>(), parts[1].parse::<u8>()) {
+                (Ok(first), Ok(second)) if first > 12 && second <= 12 => day_first += 1,
+                (Ok(first), Ok(second)) if second > 12 && first <= 12 => month_first += 1,
+                _ => {}
+            }
+        }
+
+        if month_first > day_first {
+            DateOrder::MonthFirst
+        } else {
+            DateOrder::DayFirst
+        }
+    }
+}
+
+fn split_date(text: &str) -> Vec<&str> {
+    text.split(['/', '.', '-']).collect()
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        4 | 6 | 9 | 11 => 30,
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        _ => 31,
+    }
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Date> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+
+        Some(Date { year, month, day })
+    }
+
+    // Accepts `09/01/2019`, `01.09.2019`, `01-09-2019` in the given order and ISO `2019-09-01`
+    pub fn parse(text: &str, order: DateOrder) -> Option<Date> {
+        let parts = split_date(text.trim());
+
+        if parts.len() != 3 {
+            return None;
+        }
+
+        if parts[0].len() == 4 {
+            return Date::new(
+                parts[0].parse().ok()?,
+                parts[1].parse().ok()?,
+                parts[2].parse().ok()?,
+            );
+        }
+
+        let (day, month) = match order {
+            DateOrder::DayFirst => (parts[0], parts[1]),
+            DateOrder::MonthFirst => (parts[1], parts[0]),
+        };
+
+        let year: u16 = parts[2].parse().ok()?;
+        let year = if parts[2].len() == 2 {
+            2000 + year
+        } else {
+            year
+        };
+
+        Date::new(year, month.parse().ok()?, day.parse().ok()?)
+    }
+}
+
+impl Time {
+    pub fn new(hour: u8, minute: u8, second: u8) -> Option<Time> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+
+        Some(Time {
+            hour,
+            minute,
+            second,
+        })
+    }
+
+    // Accepts `05.00.00`, `05:00:00` and `05.00`
+    pub fn parse(text: &str) -> Option<Time> {
+        let parts = text.trim().split(['.', ':']).collect::<Vec<&str>>();
+
+        match parts.as_slice() {
+            [hour, minute] => Time::new(hour.parse().ok()?, minute.parse().ok()?, 0),
+            [hour, minute, second] => Time::new(
+                hour.parse().ok()?,
+                minute.parse().ok()?,
+                second.parse().ok()?,
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn seconds_since_midnight(&self) -> u32 {
+        self.hour as u32 * 3600 + self.minute as u32 * 60 + self.second as u32
+    }
+}
+
+impl DateTime {
+    // Accepts a date and a time separated by whitespace, like `09/19/2020 08.38.36`
+    pub fn parse(text: &str, order: DateOrder) -> Option<DateTime> {
+        let (date, time) = text.trim().split_once(char::is_whitespace)?;
+
+        Some(DateTime {
+            date: Date::parse(date, order)?,
+            time: Time::parse(time)?,
+        })
+    }
+}
+
+// The video time column holds the number of seconds since the start of the video file
+pub fn parse_video_time(text: &str) -> Option<Duration> {
+    text.trim().parse().ok().map(Duration::from_secs)
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl fmt::Display for Time {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.date, self.time)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Date {
+    pub fn to_naive_date(&self) -> Option<chrono::NaiveDate> {
+        chrono::NaiveDate::from_ymd_opt(self.year as i32, self.month as u32, self.day as u32)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl Time {
+    pub fn to_naive_time(&self) -> Option<chrono::NaiveTime> {
+        chrono::NaiveTime::from_hms_opt(self.hour as u32, self.minute as u32, self.second as u32)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl DateTime {
+    pub fn to_naive_date_time(&self) -> Option<chrono::NaiveDateTime> {
+        Some(chrono::NaiveDateTime::new(
+            self.date.to_naive_date()?,
+            self.time.to_naive_time()?,
+        ))
+    }
+}
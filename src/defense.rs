@@ -0,0 +1,256 @@
+use crate::attacks::{group_entry, AttackGroup};
+use crate::data_formats::{Evaluation, Player, ScoutFile, Skill, TeamSide};
+use crate::statistics::{percentage, EvaluationCounts};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum DigSituation {
+    // Cover of the team's own attack after the opponent block
+    Cover,
+    // Opponent attack touched by the team's block first
+    BlockTouched,
+    // Any other dig, typically an attack that went past the block untouched
+    Unblocked,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DigGroup {
+    pub situation: DigSituation,
+    pub evaluations: EvaluationCounts,
+}
+
+impl DigGroup {
+    // `+` and `#` digs
+    pub fn positive_percentage(&self) -> Option<f64> {
+        percentage(
+            self.evaluations.plus + self.evaluations.hashtag,
+            self.evaluations.total(),
+        )
+    }
+
+    pub fn error_percentage(&self) -> Option<f64> {
+        percentage(self.evaluations.equal, self.evaluations.total())
+    }
+}
+
+fn dig_entry(digs: &mut Vec<DigGroup>, situation: DigSituation) -> &mut EvaluationCounts {
+    let index = match digs.iter().position(|group| group.situation == situation) {
+        Some(index) => index,
+        None => {
+            digs.push(DigGroup {
+                situation,
+                evaluations: EvaluationCounts::default(),
+            });
+            digs.len() - 1
+        }
+    };
+
+    &mut digs[index].evaluations
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FreeBallConversion {
+    pub free_balls: u32,
+    // Attacks of the possession that followed the free ball
+    pub attacks: u32,
+    pub kills: u32,
+    // Rallies won after the free ball
+    pub won: u32,
+}
+
+impl FreeBallConversion {
+    pub fn merge(&mut self, other: &FreeBallConversion) {
+        self.free_balls += other.free_balls;
+        self.attacks += other.attacks;
+        self.kills += other.kills;
+        self.won += other.won;
+    }
+
+    pub fn conversion_percentage(&self) -> Option<f64> {
+        percentage(self.won, self.free_balls)
+    }
+
+    pub fn kill_percentage(&self) -> Option<f64> {
+        percentage(self.kills, self.attacks)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerDefense {
+    pub player_number: u8,
+    pub player: Option<Player>,
+    // The player's attacks by number of blockers
+    pub attacks_by_blockers: Vec<AttackGroup<Option<u8>>>,
+    pub block: EvaluationCounts,
+    pub digs: Vec<DigGroup>,
+    // Free balls passed by the player
+    pub free_balls: FreeBallConversion,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamDefense {
+    pub team: TeamSide,
+    pub attacks_by_blockers: Vec<AttackGroup<Option<u8>>>,
+    // Attacks of the opponent, what the block touch rates are measured against
+    pub opponent_attacks: u32,
+    pub block: EvaluationCounts,
+    pub digs: Vec<DigGroup>,
+    pub free_balls: FreeBallConversion,
+    pub players: Vec<PlayerDefense>,
+}
+
+// Blocks that touched the ball, `-`, `!`, `+` and `#`. A `=` block is a net fault and a `/`
+// block an invasion, neither of which says that the block reached the ball.
+fn block_touches(block: &EvaluationCounts) -> u32 {
+    block.minus + block.exclamation + block.plus + block.hashtag
+}
+
+impl TeamDefense {
+    // Share of the opponent attacks touched by the block
+    pub fn block_touch_rate(&self) -> Option<f64> {
+        percentage(block_touches(&self.block), self.opponent_attacks)
+    }
+
+    // Share of the opponent attacks touched by the player's block
+    pub fn player_block_touch_rate(&self, player_number: u8) -> Option<f64> {
+        let player = self
+            .players
+            .iter()
+            .find(|player| player.player_number == player_number)?;
+
+        percentage(block_touches(&player.block), self.opponent_attacks)
+    }
+
+    pub fn dig(&self, situation: DigSituation) -> Option<&DigGroup> {
+        self.digs.iter().find(|group| group.situation == situation)
+    }
+
+    fn player_entry(&mut self, scout_file: &ScoutFile, player_number: u8) -> &mut PlayerDefense {
+        let index = match self
+            .players
+            .iter()
+            .position(|player| player.player_number == player_number)
+        {
+            Some(index) => index,
+            None => {
+                self.players.push(PlayerDefense {
+                    player_number,
+                    player: scout_file.player(self.team, player_number).cloned(),
+                    attacks_by_blockers: Vec::new(),
+                    block: EvaluationCounts::default(),
+                    digs: Vec::new(),
+                    free_balls: FreeBallConversion::default(),
+                });
+                self.players.len() - 1
+            }
+        };
+
+        &mut self.players[index]
+    }
+}
+
+// Attacks against the block, block touches, digs and free balls of both teams, home first
+pub fn defense_statistics(scout_file: &ScoutFile) -> Vec<TeamDefense> {
+    let mut teams = TeamSide::ALL
+        .into_iter()
+        .map(|team| TeamDefense {
+            team,
+            attacks_by_blockers: Vec::new(),
+            opponent_attacks: 0,
+            block: EvaluationCounts::default(),
+            digs: Vec::new(),
+            free_balls: FreeBallConversion::default(),
+            players: Vec::new(),
+        })
+        .collect::<Vec<TeamDefense>>();
+
+    for rally in scout_file.rallies() {
+        let touches = rally.touches().collect::<Vec<_>>();
+
+        for (index, touch) in touches.iter().enumerate() {
+            let team = &mut teams[touch.team as usize];
+
+            match touch.skill {
+                Skill::Attack => {
+                    group_entry(&mut team.attacks_by_blockers, touch.blockers()).add(touch);
+                    group_entry(
+                        &mut team
+                            .player_entry(scout_file, touch.player_number)
+                            .attacks_by_blockers,
+                        touch.blockers(),
+                    )
+                    .add(touch);
+                }
+                Skill::Block => {
+                    team.block.add(touch.evaluation);
+                    team.player_entry(scout_file, touch.player_number)
+                        .block
+                        .add(touch.evaluation);
+                }
+                Skill::Dig => {
+                    let situation = match index.checked_sub(1).map(|previous| touches[previous]) {
+                        Some(previous) if previous.skill == Skill::Block => {
+                            if previous.team == touch.team {
+                                DigSituation::BlockTouched
+                            } else {
+                                DigSituation::Cover
+                            }
+                        }
+                        _ => DigSituation::Unblocked,
+                    };
+
+                    dig_entry(&mut team.digs, situation).add(touch.evaluation);
+                    dig_entry(
+                        &mut team.player_entry(scout_file, touch.player_number).digs,
+                        situation,
+                    )
+                    .add(touch.evaluation);
+                }
+                Skill::FreeBall => {
+                    let attacks = touches[index + 1..]
+                        .iter()
+                        .take_while(|next| next.team == touch.team)
+                        .filter(|next| next.skill == Skill::Attack)
+                        .collect::<Vec<_>>();
+                    let kills = attacks
+                        .iter()
+                        .filter(|attack| attack.evaluation == Evaluation::Hashtag)
+                        .count() as u32;
+                    let won = (rally.winner() == Some(touch.team)) as u32;
+
+                    let conversion = FreeBallConversion {
+                        free_balls: 1,
+                        attacks: attacks.len() as u32,
+                        kills,
+                        won,
+                    };
+
+                    team.free_balls.merge(&conversion);
+                    team.player_entry(scout_file, touch.player_number)
+                        .free_balls
+                        .merge(&conversion);
+                }
+                _ => {}
+            }
+
+            if touch.skill == Skill::Attack {
+                teams[touch.team.opponent() as usize].opponent_attacks += 1;
+            }
+        }
+    }
+
+    for team in &mut teams {
+        team.attacks_by_blockers.sort_by_key(|group| group.key);
+
+        for player in &mut team.players {
+            player.attacks_by_blockers.sort_by_key(|group| group.key);
+        }
+    }
+
+    teams
+}
@@ -0,0 +1,198 @@
+use crate::court::{CourtCoordinate, CourtHalf};
+use crate::data_formats::{CodeExplanation, Evaluation, ScoutFile, Skill, TeamSide};
+
+// Touches to keep, None matches everything
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchFilter {
+    pub team: Option<TeamSide>,
+    pub player_number: Option<u8>,
+    pub skill: Option<Skill>,
+    pub evaluation: Option<Evaluation>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum TrajectoryPoint {
+    Start,
+    Mid,
+    End,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Trajectory {
+    pub action_index: usize,
+    pub team: TeamSide,
+    pub player_number: u8,
+    pub skill: Skill,
+    pub evaluation: Evaluation,
+    pub start: Option<CourtCoordinate>,
+    pub mid: Option<CourtCoordinate>,
+    pub end: Option<CourtCoordinate>,
+}
+
+impl Trajectory {
+    pub fn point(&self, point: TrajectoryPoint) -> Option<CourtCoordinate> {
+        match point {
+            TrajectoryPoint::Start => self.start,
+            TrajectoryPoint::Mid => self.mid,
+            TrajectoryPoint::End => self.end,
+        }
+    }
+
+    // Start, mid and end point in that order, leaving out the missing ones
+    pub fn points(&self) -> Vec<CourtCoordinate> {
+        [self.start, self.mid, self.end]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    // Teams change ends between sets. This turns the trajectory around the centre of the court
+    // when it starts on the far half, so that trajectories of different sets can be drawn together.
+    pub fn normalized(&self) -> Trajectory {
+        if self.start.map(|start| start.half()) != Some(CourtHalf::Far) {
+            return self.clone();
+        }
+
+        Trajectory {
+            start: self.start.map(turn_around),
+            mid: self.mid.map(turn_around),
+            end: self.end.map(turn_around),
+            ..self.clone()
+        }
+    }
+}
+
+// Row 0 and column 0 have no counterpart on the grid and end up on row or column 99
+fn turn_around(coordinate: CourtCoordinate) -> CourtCoordinate {
+    CourtCoordinate {
+        x: (100 - coordinate.x).min(99),
+        y: (100 - coordinate.y).min(99),
+    }
+}
+
+// Every touch that matches the filter and has at least one coordinate, in the order of the file
+pub fn trajectories(scout_file: &ScoutFile, filter: &TouchFilter) -> Vec<Trajectory> {
+    let mut trajectories = Vec::new();
+
+    for (action_index, action) in scout_file.actions.iter().enumerate() {
+        let touch = match &action.code_explanation {
+            CodeExplanation::ManualCode(touch) => touch,
+            _ => continue,
+        };
+
+        if filter.team.is_some_and(|team| team != touch.team)
+            || filter
+                .player_number
+                .is_some_and(|number| number != touch.player_number)
+            || filter.skill.is_some_and(|skill| skill != touch.skill)
+            || filter
+                .evaluation
+                .is_some_and(|evaluation| evaluation != touch.evaluation)
+        {
+            continue;
+        }
+
+        let trajectory = Trajectory {
+            action_index,
+            team: touch.team,
+            player_number: touch.player_number,
+            skill: touch.skill,
+            evaluation: touch.evaluation,
+            start: action.start_coordinate,
+            mid: action.mid_coordinate,
+            end: action.end_coordinate,
+        };
+
+        if !trajectory.points().is_empty() {
+            trajectories.push(trajectory);
+        }
+    }
+
+    trajectories
+}
+
+// Counts of points binned on a `columns` x `rows` grid laid over the whole Data Volley grid,
+// row 0 being the near end of the court
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Heatmap {
+    pub columns: u8,
+    pub rows: u8,
+    // Row by row, `columns` counts per row
+    pub counts: Vec<u32>,
+    pub total: u32,
+}
+
+impl Heatmap {
+    pub fn new(columns: u8, rows: u8) -> Heatmap {
+        let columns = columns.clamp(1, 100);
+        let rows = rows.clamp(1, 100);
+
+        Heatmap {
+            columns,
+            rows,
+            counts: vec![0; columns as usize * rows as usize],
+            total: 0,
+        }
+    }
+
+    // Column and row of the bin holding the coordinate
+    pub fn bin(&self, coordinate: CourtCoordinate) -> (u8, u8) {
+        (
+            (coordinate.x as u32 * self.columns as u32 / 100) as u8,
+            (coordinate.y as u32 * self.rows as u32 / 100) as u8,
+        )
+    }
+
+    pub fn add(&mut self, coordinate: CourtCoordinate) {
+        let (column, row) = self.bin(coordinate);
+
+        self.counts[row as usize * self.columns as usize + column as usize] += 1;
+        self.total += 1;
+    }
+
+    pub fn get(&self, column: u8, row: u8) -> u32 {
+        if column >= self.columns || row >= self.rows {
+            return 0;
+        }
+
+        self.counts[row as usize * self.columns as usize + column as usize]
+    }
+
+    // Fraction of all points that fall in the bin, from 0 to 1
+    pub fn density(&self, column: u8, row: u8) -> Option<f64> {
+        if self.total == 0 {
+            None
+        } else {
+            Some(self.get(column, row) as f64 / self.total as f64)
+        }
+    }
+
+    // Highest count of a single bin, handy to scale colours
+    pub fn max(&self) -> u32 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+}
+
+// Bins one point of every trajectory, trajectories without that point are left out
+pub fn heatmap(
+    trajectories: &[Trajectory],
+    point: TrajectoryPoint,
+    columns: u8,
+    rows: u8,
+) -> Heatmap {
+    let mut heatmap = Heatmap::new(columns, rows);
+
+    for coordinate in trajectories
+        .iter()
+        .filter_map(|trajectory| trajectory.point(point))
+    {
+        heatmap.add(coordinate);
+    }
+
+    heatmap
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::char::is_combining_mark;
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::UnicodeNormalization;
+
+use crate::data_formats::{Player, ScoutFile, TeamSide};
+
+// Letters that are not a base letter plus combining marks, so NFD leaves them as they are
+fn fold_letter(c: char) -> Option<&'static str> {
+    Some(match c {
+        'ł' => "l",
+        'ø' => "o",
+        'đ' | 'ð' => "d",
+        'ħ' => "h",
+        'ı' => "i",
+        'ŀ' => "l",
+        'ŧ' => "t",
+        'þ' => "th",
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        _ => return None,
+    })
+}
+
+// Lowercase name with the combining marks of the NFD decomposition dropped
+#[cfg(feature = "unicode-normalization")]
+fn strip_marks(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .nfd()
+        .filter(|&c| !is_combining_mark(c))
+        .collect()
+}
+
+// Lowercase name with the letters of Latin-1 and Latin Extended-A replaced by their base letter,
+// other scripts keep their diacritics without the `unicode-normalization` feature
+#[cfg(not(feature = "unicode-normalization"))]
+fn strip_marks(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(|c| match c {
+            'à'..='å' | 'ā' | 'ă' | 'ą' => 'a',
+            'ç' | 'ć' | 'ĉ' | 'ċ' | 'č' => 'c',
+            'ď' => 'd',
+            'è'..='ë' | 'ē' | 'ĕ' | 'ė' | 'ę' | 'ě' => 'e',
+            'ĝ' | 'ğ' | 'ġ' | 'ģ' => 'g',
+            'ĥ' => 'h',
+            'ì'..='ï' | 'ĩ' | 'ī' | 'ĭ' | 'į' => 'i',
+            'ĵ' => 'j',
+            'ķ' => 'k',
+            'ĺ' | 'ļ' | 'ľ' => 'l',
+            'ñ' | 'ń' | 'ņ' | 'ň' => 'n',
+            'ò'..='ö' | 'ō' | 'ŏ' | 'ő' => 'o',
+            'ŕ' | 'ŗ' | 'ř' => 'r',
+            'ś' | 'ŝ' | 'ş' | 'š' | 'ș' => 's',
+            'ţ' | 'ť' | 'ț' => 't',
+            'ù'..='ü' | 'ũ' | 'ū' | 'ŭ' | 'ů' | 'ű' | 'ų' => 'u',
+            'ŵ' => 'w',
+            'ý' | 'ÿ' | 'ŷ' => 'y',
+            'ź' | 'ż' | 'ž' => 'z',
+            c => c,
+        })
+        .collect()
+}
+
+// Lowercase words of the name without diacritics or punctuation, sorted so that `Shields Shannon`
+// and `shannon  SHIELDS` give the same result. Letters are lowercased with `char::to_lowercase`
+// before the diacritics are dropped, `ŠIMIĆ` becomes `simic`.
+pub fn normalize_name(name: &str) -> String {
+    let mut folded = String::new();
+
+    for c in strip_marks(name).chars() {
+        match fold_letter(c) {
+            Some(letters) => folded.push_str(letters),
+            None if c.is_alphanumeric() => folded.push(c),
+            None => folded.push(' '),
+        }
+    }
+
+    let mut words = folded
+        .split_whitespace()
+        .map(|word| word.to_string())
+        .collect::<Vec<String>>();
+
+    words.sort();
+    words.join(" ")
+}
+
+fn player_name(player: &Player) -> String {
+    normalize_name(&format!("{} {}", player.name, player.last_name))
+}
+
+fn name_key(team_id: &str, name: &str) -> String {
+    format!("{}:{}", team_id.trim(), name)
+}
+
+// Identity keys to use instead of the automatic matching, for players whose id and name both
+// changed between files
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdentityOverrides {
+    // Player id to identity key
+    pub by_player_id: HashMap<String, String>,
+    // `team id:normalized name` to identity key
+    pub by_name: HashMap<String, String>,
+}
+
+impl IdentityOverrides {
+    pub fn add_player_id(&mut self, player_id: &str, key: &str) {
+        self.by_player_id
+            .insert(player_id.trim().to_string(), key.to_string());
+    }
+
+    pub fn add_name(&mut self, team_id: &str, name: &str, key: &str) {
+        self.by_name
+            .insert(name_key(team_id, &normalize_name(name)), key.to_string());
+    }
+
+    fn lookup(&self, team_id: &str, player: &Player) -> Option<&String> {
+        self.by_player_id
+            .get(player.player_id.trim())
+            .or_else(|| self.by_name.get(&name_key(team_id, &player_name(player))))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerIdentity {
+    // The override key, otherwise the first player id seen, otherwise `team id:normalized name`
+    pub key: String,
+    // Roster entry the identity was created from
+    pub player: Player,
+    pub player_ids: Vec<String>,
+    pub team_ids: Vec<String>,
+    pub names: Vec<String>,
+}
+
+impl PlayerIdentity {
+    fn add(&mut self, team_id: &str, player: &Player) {
+        for (values, value) in [
+            (&mut self.player_ids, player.player_id.trim().to_string()),
+            (&mut self.team_ids, team_id.trim().to_string()),
+            (&mut self.names, player_name(player)),
+        ] {
+            if !value.is_empty() && !values.contains(&value) {
+                values.push(value);
+            }
+        }
+    }
+}
+
+// Links roster entries of different files to one person. A player is matched on the override
+// table first, then on player id and then on normalized name within the same team. VolleyMetrics
+// and Data Volley number players differently, so the name match is what links their files.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IdentityResolver {
+    pub overrides: IdentityOverrides,
+    pub identities: Vec<PlayerIdentity>,
+}
+
+impl IdentityResolver {
+    pub fn new(overrides: IdentityOverrides) -> IdentityResolver {
+        IdentityResolver {
+            overrides,
+            identities: Vec::new(),
+        }
+    }
+
+    // `claimed` holds the identities taken by other entries of the same file, neither an id nor a
+    // name match ever links two entries of one file. An id matches across teams so a player who
+    // moved club keeps one identity, a name only matches within the same team.
+    fn find(&self, team_id: &str, player: &Player, claimed: &[usize]) -> Option<usize> {
+        if let Some(key) = self.overrides.lookup(team_id, player) {
+            return self
+                .identities
+                .iter()
+                .position(|identity| identity.key == *key);
+        }
+
+        let player_id = player.player_id.trim();
+
+        if !player_id.is_empty() {
+            if let Some(index) = self
+                .identities
+                .iter()
+                .enumerate()
+                .position(|(index, identity)| {
+                    !claimed.contains(&index)
+                        && identity.player_ids.iter().any(|id| id == player_id)
+                })
+            {
+                return Some(index);
+            }
+        }
+
+        let name = player_name(player);
+
+        if name.is_empty() {
+            return None;
+        }
+
+        let team_id = team_id.trim();
+
+        self.identities
+            .iter()
+            .enumerate()
+            .position(|(index, identity)| {
+                !claimed.contains(&index)
+                    && identity.team_ids.iter().any(|id| id == team_id)
+                    && identity.names.contains(&name)
+            })
+    }
+
+    // Key of the identity of a player of the team `team_id` (`Team::team_id`, the `team_id` of
+    // the roster entry only tells home from visiting), creating the identity when the player is new
+    pub fn resolve(&mut self, team_id: &str, player: &Player) -> String {
+        let index = self.resolve_index(team_id, player, &[]);
+
+        self.identities[index].key.clone()
+    }
+
+    fn resolve_index(&mut self, team_id: &str, player: &Player, claimed: &[usize]) -> usize {
+        let index = match self.find(team_id, player, claimed) {
+            Some(index) => index,
+            None => {
+                let key = match self.overrides.lookup(team_id, player) {
+                    Some(key) => key.clone(),
+                    None if !player.player_id.trim().is_empty() => {
+                        player.player_id.trim().to_string()
+                    }
+                    None => name_key(team_id, &player_name(player)),
+                };
+
+                // Two entries of one file with the same name and no id
+                let mut unique = key.clone();
+                let mut count = 1;
+
+                while self.identity(&unique).is_some() {
+                    count += 1;
+                    unique = format!("{}#{}", key, count);
+                }
+
+                self.identities.push(PlayerIdentity {
+                    key: unique,
+                    player: player.clone(),
+                    player_ids: Vec::new(),
+                    team_ids: Vec::new(),
+                    names: Vec::new(),
+                });
+                self.identities.len() - 1
+            }
+        };
+
+        self.identities[index].add(team_id, player);
+        index
+    }
+
+    // Identity keys of both rosters by side and shirt number
+    pub fn resolve_file(&mut self, scout_file: &ScoutFile) -> HashMap<(TeamSide, u8), String> {
+        let mut keys = HashMap::new();
+        let mut claimed = Vec::new();
+
+        for side in TeamSide::ALL {
+            let team_id = &scout_file.team(side).team_id;
+
+            for player in scout_file.players(side) {
+                let index = self.resolve_index(team_id, player, &claimed);
+
+                claimed.push(index);
+                keys.insert(
+                    (side, player.player_number),
+                    self.identities[index].key.clone(),
+                );
+            }
+        }
+
+        keys
+    }
+
+    pub fn identity(&self, key: &str) -> Option<&PlayerIdentity> {
+        self.identities.iter().find(|identity| identity.key == key)
+    }
+}
@@ -2,7 +2,29 @@ use std::io::{self, BufReader, Read};
 
 use data_formats::{read_scout_file, ScoutFile};
 
+pub mod attacks;
+pub mod chain;
+pub mod collection;
+pub mod court;
 pub mod data_formats;
+pub mod datetime;
+pub mod defense;
+pub mod heatmap;
+pub mod identity;
+pub mod momentum;
+pub mod rally;
+pub mod rotations;
+pub mod scouting;
+pub mod serves;
+pub mod setter;
+pub mod simulation;
+pub mod statistics;
+#[cfg(feature = "svg")]
+pub mod svg;
+pub mod validation;
+pub mod value;
+pub mod win_probability;
+pub mod zones;
 
 pub fn read(mut input: impl Read) -> Result<ScoutFile, io::Error> {
     read_scout_file(&mut input)
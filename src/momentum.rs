@@ -0,0 +1,209 @@
+use crate::data_formats::{AutomaticCodeExplanation, CodeExplanation, ScoutFile, Skill, TeamSide};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimelinePoint {
+    // Index into `ScoutFile::actions` of the point code
+    pub action_index: usize,
+    pub team: TeamSide,
+    pub serving_team: Option<TeamSide>,
+    // Shirt number of the server of the rally
+    pub server: Option<u8>,
+    // Score after the point
+    pub home_score: u8,
+    pub visiting_score: u8,
+}
+
+impl TimelinePoint {
+    // Home score minus visiting score after the point, positive while the home team leads
+    pub fn difference(&self) -> i16 {
+        self.home_score as i16 - self.visiting_score as i16
+    }
+}
+
+// Consecutive points won by one team, e.g. a 4-0 run
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Run {
+    pub team: TeamSide,
+    // Position in `SetTimeline::points` of the first point of the run
+    pub first_point: usize,
+    pub length: u8,
+    // Score before the run started
+    pub home_score: u8,
+    pub visiting_score: u8,
+    // Shirt number of the player of the team who served during the run. The first point of a run
+    // is usually a side-out, so this is the server of the second point.
+    pub server: Option<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TimeoutMark {
+    pub action_index: usize,
+    pub team: TeamSide,
+    // Number of points played in the set before the timeout
+    pub points_played: usize,
+    pub home_score: u8,
+    pub visiting_score: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetTimeline {
+    pub set_number: u8,
+    pub points: Vec<TimelinePoint>,
+    pub runs: Vec<Run>,
+    pub timeouts: Vec<TimeoutMark>,
+}
+
+impl SetTimeline {
+    // The first of the longest runs of the team
+    pub fn longest_run(&self, team: TeamSide) -> Option<&Run> {
+        longest(self.runs.iter(), team)
+    }
+
+    // Runs of at least `length` points, e.g. 3 for every 3-0 run or longer
+    pub fn runs_of(&self, length: u8) -> impl Iterator<Item = &Run> {
+        self.runs.iter().filter(move |run| run.length >= length)
+    }
+}
+
+fn longest<'a>(runs: impl Iterator<Item = &'a Run>, team: TeamSide) -> Option<&'a Run> {
+    runs.filter(|run| run.team == team)
+        .fold(None, |longest: Option<&Run>, run| match longest {
+            Some(longest) if longest.length >= run.length => Some(longest),
+            _ => Some(run),
+        })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timeline {
+    pub sets: Vec<SetTimeline>,
+}
+
+impl Timeline {
+    pub fn longest_run(&self, team: TeamSide) -> Option<&Run> {
+        longest(self.sets.iter().flat_map(|set| set.runs.iter()), team)
+    }
+}
+
+fn runs(points: &[TimelinePoint]) -> Vec<Run> {
+    let mut runs: Vec<Run> = Vec::new();
+
+    for (index, point) in points.iter().enumerate() {
+        match runs.last_mut() {
+            Some(run) if run.team == point.team => {
+                run.length += 1;
+
+                if run.server.is_none() && point.serving_team == Some(point.team) {
+                    run.server = point.server;
+                }
+            }
+            _ => {
+                let (home_score, visiting_score) = match index {
+                    0 => (0, 0),
+                    _ => (
+                        points[index - 1].home_score,
+                        points[index - 1].visiting_score,
+                    ),
+                };
+
+                runs.push(Run {
+                    team: point.team,
+                    first_point: index,
+                    length: 1,
+                    home_score,
+                    visiting_score,
+                    server: point
+                        .server
+                        .filter(|_| point.serving_team == Some(point.team)),
+                });
+            }
+        }
+    }
+
+    runs
+}
+
+fn set_entry(sets: &mut Vec<SetTimeline>, set_number: u8) -> &mut SetTimeline {
+    let index = match sets.iter().position(|set| set.set_number == set_number) {
+        Some(index) => index,
+        None => {
+            sets.push(SetTimeline {
+                set_number,
+                points: Vec::new(),
+                runs: Vec::new(),
+                timeouts: Vec::new(),
+            });
+            sets.len() - 1
+        }
+    };
+
+    &mut sets[index]
+}
+
+// Point by point timeline of every set from the point codes, with the runs and timeouts
+pub fn timeline(scout_file: &ScoutFile) -> Timeline {
+    let mut sets: Vec<SetTimeline> = Vec::new();
+
+    for rally in scout_file.rallies() {
+        let point = match rally.point {
+            Some(point) => point,
+            None => continue,
+        };
+
+        let serve = rally
+            .touches()
+            .next()
+            .filter(|touch| touch.skill == Skill::Serve);
+
+        set_entry(&mut sets, rally.set).points.push(TimelinePoint {
+            action_index: rally.start_index + rally.actions.len() - 1,
+            team: point.team,
+            serving_team: serve.map(|serve| serve.team),
+            server: serve.map(|serve| serve.player_number),
+            home_score: point.home_score,
+            visiting_score: point.visiting_score,
+        });
+    }
+
+    // Timeouts are read from the rows themselves, a timeout after the last point of a set is not
+    // part of any rally
+    let mut set_number = 1;
+    let mut points_played = 0;
+    let mut score = (0, 0);
+
+    for (action_index, action) in scout_file.actions.iter().enumerate() {
+        match &action.code_explanation {
+            CodeExplanation::AutomaticCode(AutomaticCodeExplanation::Point(point)) => {
+                points_played += 1;
+                score = (point.home_score, point.visiting_score);
+            }
+            CodeExplanation::AutomaticCode(AutomaticCodeExplanation::Timeout(timeout)) => {
+                set_entry(&mut sets, set_number).timeouts.push(TimeoutMark {
+                    action_index,
+                    team: timeout.team,
+                    points_played,
+                    home_score: score.0,
+                    visiting_score: score.1,
+                });
+            }
+            CodeExplanation::SetEnd(number) => {
+                set_number = number + 1;
+                points_played = 0;
+                score = (0, 0);
+            }
+            _ => {}
+        }
+    }
+
+    sets.sort_by_key(|set| set.set_number);
+
+    for set in &mut sets {
+        set.runs = runs(&set.points);
+    }
+
+    Timeline { sets }
+}
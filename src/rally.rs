@@ -0,0 +1,303 @@
+use crate::data_formats::{
+    Action, AutomaticCodeExplanation, CodeExplanation, Evaluation, ManualCodeExplanation, Point,
+    ScoutFile, Skill, TeamSide,
+};
+
+// How the winner of a rally earned the point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum PointOrigin {
+    Serve,
+    Attack,
+    Block,
+    OpponentError,
+    // No touch explains the point, e.g. a penalty or a rally without touches
+    Other,
+}
+
+// A rally is every row from the end of the previous rally up to and including the point code
+// that ends it, so the setter positions and substitutions entered before the serve belong to it.
+#[derive(Debug)]
+pub struct Rally<'a> {
+    pub set: u8,
+    // Index into `ScoutFile::actions` of the first row of the rally
+    pub start_index: usize,
+    pub actions: &'a [Action],
+    pub home_setter_position: Option<u8>,
+    pub visiting_setter_position: Option<u8>,
+    // Shirt number of the setter from the `*P19` codes
+    pub home_setter: Option<u8>,
+    pub visiting_setter: Option<u8>,
+    // Score before the rally was played
+    pub home_score: u8,
+    pub visiting_score: u8,
+    // None when the rally was cut off by the end of a set or the end of the file
+    pub point: Option<&'a Point>,
+}
+
+// Score of a team compared to its opponent before the rally
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ScoreSituation {
+    Leading,
+    Tied,
+    Trailing,
+}
+
+// Consecutive touches of one team, a new possession starts whenever the other team touches the
+// ball. A block therefore starts the possession of the blocking team.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Possession<'a> {
+    pub team: TeamSide,
+    pub touches: Vec<&'a ManualCodeExplanation>,
+}
+
+impl<'a> Possession<'a> {
+    pub fn reception(&self) -> Option<&'a ManualCodeExplanation> {
+        self.touches
+            .iter()
+            .copied()
+            .find(|touch| touch.skill == Skill::Reception)
+    }
+
+    // The last set before the touch at `index`
+    pub fn set_before(&self, index: usize) -> Option<&'a ManualCodeExplanation> {
+        self.touches[..index]
+            .iter()
+            .copied()
+            .rev()
+            .find(|touch| touch.skill == Skill::Set)
+    }
+
+    // Attacks together with their position in `touches`
+    pub fn attacks(&self) -> impl Iterator<Item = (usize, &'a ManualCodeExplanation)> + '_ {
+        self.touches
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, touch)| touch.skill == Skill::Attack)
+    }
+}
+
+impl<'a> Rally<'a> {
+    pub fn possessions(&self) -> Vec<Possession<'a>> {
+        let mut possessions: Vec<Possession<'a>> = Vec::new();
+
+        for touch in self.touches() {
+            match possessions.last_mut() {
+                Some(possession) if possession.team == touch.team => possession.touches.push(touch),
+                _ => possessions.push(Possession {
+                    team: touch.team,
+                    touches: vec![touch],
+                }),
+            }
+        }
+
+        possessions
+    }
+
+    pub fn touches(&self) -> impl Iterator<Item = &'a ManualCodeExplanation> {
+        self.actions
+            .iter()
+            .filter_map(|action| match &action.code_explanation {
+                CodeExplanation::ManualCode(code) => Some(code),
+                _ => None,
+            })
+    }
+
+    // Touches together with the row they were scouted on
+    pub fn touch_actions(&self) -> impl Iterator<Item = (&'a Action, &'a ManualCodeExplanation)> {
+        self.actions
+            .iter()
+            .filter_map(|action| match &action.code_explanation {
+                CodeExplanation::ManualCode(code) => Some((action, code)),
+                _ => None,
+            })
+    }
+
+    pub fn serving_team(&self) -> Option<TeamSide> {
+        self.touches()
+            .next()
+            .filter(|touch| matches!(touch.skill, Skill::Serve))
+            .map(|touch| touch.team)
+    }
+
+    pub fn receiving_team(&self) -> Option<TeamSide> {
+        self.serving_team().map(|team| team.opponent())
+    }
+
+    pub fn winner(&self) -> Option<TeamSide> {
+        self.point.map(|point| point.team)
+    }
+
+    // The receiving team won the rally
+    pub fn is_side_out(&self) -> bool {
+        match (self.serving_team(), self.winner()) {
+            (Some(serving), Some(winner)) => serving != winner,
+            _ => false,
+        }
+    }
+
+    pub fn setter_position(&self, team: TeamSide) -> Option<u8> {
+        match team {
+            TeamSide::Home => self.home_setter_position,
+            TeamSide::Visiting => self.visiting_setter_position,
+        }
+    }
+
+    // Looks at the last touch of the winner first so an ace followed by the reception error
+    // counts as a serve point, then at the last touch of the loser.
+    pub fn point_origin(&self) -> Option<PointOrigin> {
+        let winner = self.winner()?;
+
+        let winner_touch = self.touches().filter(|touch| touch.team == winner).last();
+
+        if let Some(touch) = winner_touch {
+            if touch.evaluation == Evaluation::Hashtag {
+                match touch.skill {
+                    Skill::Serve => return Some(PointOrigin::Serve),
+                    Skill::Attack => return Some(PointOrigin::Attack),
+                    Skill::Block => return Some(PointOrigin::Block),
+                    _ => {}
+                }
+            }
+        }
+
+        let loser_touch = self.touches().filter(|touch| touch.team != winner).last();
+
+        Some(match loser_touch {
+            Some(touch) if touch.evaluation == Evaluation::Equal => PointOrigin::OpponentError,
+            // A block with `/` is a net invasion
+            Some(touch) if touch.skill == Skill::Block && touch.evaluation == Evaluation::Slash => {
+                PointOrigin::OpponentError
+            }
+            Some(touch)
+                if touch.skill == Skill::Attack && touch.evaluation == Evaluation::Slash =>
+            {
+                PointOrigin::Block
+            }
+            _ => PointOrigin::Other,
+        })
+    }
+
+    pub fn setter(&self, team: TeamSide) -> Option<u8> {
+        match team {
+            TeamSide::Home => self.home_setter,
+            TeamSide::Visiting => self.visiting_setter,
+        }
+    }
+
+    pub fn score(&self, team: TeamSide) -> u8 {
+        match team {
+            TeamSide::Home => self.home_score,
+            TeamSide::Visiting => self.visiting_score,
+        }
+    }
+
+    pub fn score_situation(&self, team: TeamSide) -> ScoreSituation {
+        match self.score(team).cmp(&self.score(team.opponent())) {
+            std::cmp::Ordering::Greater => ScoreSituation::Leading,
+            std::cmp::Ordering::Equal => ScoreSituation::Tied,
+            std::cmp::Ordering::Less => ScoreSituation::Trailing,
+        }
+    }
+}
+
+impl ScoutFile {
+    pub fn rallies(&self) -> Vec<Rally<'_>> {
+        let mut rallies = Vec::new();
+
+        let mut set = 1;
+        let mut start_index = 0;
+        let mut home_setter_position = None;
+        let mut visiting_setter_position = None;
+        let mut home_setter = None;
+        let mut visiting_setter = None;
+        let mut home_score = 0;
+        let mut visiting_score = 0;
+        let mut has_touches = false;
+
+        for (index, action) in self.actions.iter().enumerate() {
+            match &action.code_explanation {
+                CodeExplanation::ManualCode(_) => has_touches = true,
+                CodeExplanation::AutomaticCode(AutomaticCodeExplanation::SetterPosition(
+                    position,
+                )) => match position.team {
+                    TeamSide::Home => home_setter_position = Some(position.setter_position),
+                    TeamSide::Visiting => visiting_setter_position = Some(position.setter_position),
+                },
+                CodeExplanation::AutomaticCode(AutomaticCodeExplanation::SetterReplacement(
+                    replacement,
+                )) => match replacement.team {
+                    TeamSide::Home => home_setter = Some(replacement.setter_number),
+                    TeamSide::Visiting => visiting_setter = Some(replacement.setter_number),
+                },
+                CodeExplanation::AutomaticCode(AutomaticCodeExplanation::Point(point)) => {
+                    rallies.push(Rally {
+                        set,
+                        start_index,
+                        actions: &self.actions[start_index..=index],
+                        home_setter_position,
+                        visiting_setter_position,
+                        home_setter,
+                        visiting_setter,
+                        home_score,
+                        visiting_score,
+                        point: Some(point),
+                    });
+
+                    home_score = point.home_score;
+                    visiting_score = point.visiting_score;
+                    start_index = index + 1;
+                    has_touches = false;
+                }
+                CodeExplanation::SetEnd(set_number) => {
+                    if has_touches {
+                        rallies.push(Rally {
+                            set,
+                            start_index,
+                            actions: &self.actions[start_index..index],
+                            home_setter_position,
+                            visiting_setter_position,
+                            home_setter,
+                            visiting_setter,
+                            home_score,
+                            visiting_score,
+                            point: None,
+                        });
+                    }
+
+                    set = set_number + 1;
+                    home_setter_position = None;
+                    visiting_setter_position = None;
+                    home_setter = None;
+                    visiting_setter = None;
+                    home_score = 0;
+                    visiting_score = 0;
+                    start_index = index + 1;
+                    has_touches = false;
+                }
+                _ => {}
+            }
+        }
+
+        if has_touches {
+            rallies.push(Rally {
+                set,
+                start_index,
+                actions: &self.actions[start_index..],
+                home_setter_position,
+                visiting_setter_position,
+                home_setter,
+                visiting_setter,
+                home_score,
+                visiting_score,
+                point: None,
+            });
+        }
+
+        rallies
+    }
+}
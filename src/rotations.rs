@@ -0,0 +1,80 @@
+use crate::data_formats::{ScoutFile, TeamSide};
+use crate::rally::Rally;
+use crate::statistics::TeamStatistics;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RotationStatistics {
+    // Setter position of the team during the rally, from the `*z1`...`*z6` codes
+    pub rotation: u8,
+    pub statistics: TeamStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetRotations {
+    pub set_number: u8,
+    pub rotations: Vec<RotationStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RotationReport {
+    pub team: TeamSide,
+    pub match_rotations: Vec<RotationStatistics>,
+    pub sets: Vec<SetRotations>,
+}
+
+fn empty_rotations() -> Vec<RotationStatistics> {
+    (1..=6)
+        .map(|rotation| RotationStatistics {
+            rotation,
+            statistics: TeamStatistics::default(),
+        })
+        .collect()
+}
+
+fn add_rally(rotations: &mut [RotationStatistics], team: TeamSide, rally: &Rally) {
+    let rotation = match rally.setter_position(team) {
+        Some(rotation @ 1..=6) => rotation,
+        _ => return,
+    };
+
+    rotations[rotation as usize - 1]
+        .statistics
+        .add_rally(team, rally);
+}
+
+// Side-out and break-point figures of both teams in each of their six rotations. Rallies without
+// a setter position are left out.
+pub fn rotation_statistics(scout_file: &ScoutFile) -> Vec<RotationReport> {
+    let rallies = scout_file.rallies();
+
+    TeamSide::ALL
+        .iter()
+        .map(|&team| {
+            let mut report = RotationReport {
+                team,
+                match_rotations: empty_rotations(),
+                sets: Vec::new(),
+            };
+
+            for rally in &rallies {
+                add_rally(&mut report.match_rotations, team, rally);
+
+                if report.sets.last().map(|set| set.set_number) != Some(rally.set) {
+                    report.sets.push(SetRotations {
+                        set_number: rally.set,
+                        rotations: empty_rotations(),
+                    });
+                }
+
+                if let Some(set) = report.sets.last_mut() {
+                    add_rally(&mut set.rotations, team, rally);
+                }
+            }
+
+            report
+        })
+        .collect()
+}
@@ -0,0 +1,364 @@
+use crate::attacks::{group_entry, AttackGroup, AttackStatistics};
+use crate::data_formats::{Player, ScoutFile, TeamSide};
+use crate::identity::IdentityResolver;
+use crate::rally::Rally;
+use crate::setter::{credited_setter, Distribution};
+use crate::statistics::{percentage, EvaluationCounts};
+use crate::zones::{reception_matrices, serve_matrices, ServeCell};
+
+// Zones with fewer receptions than this are not considered for `weakest_zone`
+const MIN_ZONE_RECEPTIONS: u32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum AttackPhase {
+    // Attacks of a possession that started with the reception of a serve
+    SideOut,
+    Transition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RotationCombinations {
+    pub rotation: u8,
+    // Most used first, None for attacks without a combination
+    pub combinations: Vec<AttackGroup<Option<String>>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetterTendency {
+    // Key of the `PlayerIdentity`, or `#7` for a shirt number that is not on the roster
+    pub key: String,
+    // Shirt number in the last file the player appeared in
+    pub setter_number: u8,
+    pub player: Option<Player>,
+    pub overall: Distribution,
+    // Sets while the team trailed or once either team reached 20 points (10 in the fifth set)
+    pub under_pressure: Distribution,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AttackerStatistics {
+    // Key of the `PlayerIdentity`, or `#7` for a shirt number that is not on the roster
+    pub key: String,
+    // Shirt number in the last file the player appeared in
+    pub player_number: u8,
+    pub player: Option<Player>,
+    pub statistics: AttackStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhaseAttackers {
+    pub phase: AttackPhase,
+    // Most kills first
+    pub attackers: Vec<AttackerStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ZoneReceptions {
+    pub zone: Option<u8>,
+    pub evaluations: EvaluationCounts,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReceiverWeakness {
+    // Key of the `PlayerIdentity`, or `#7` for a shirt number that is not on the roster
+    pub key: String,
+    // Shirt number in the last file the player appeared in
+    pub player_number: u8,
+    pub player: Option<Player>,
+    pub evaluations: EvaluationCounts,
+    pub by_zone: Vec<ZoneReceptions>,
+    // Zone with the lowest share of `+` and `#` receptions
+    pub weakest_zone: Option<u8>,
+}
+
+impl ReceiverWeakness {
+    pub fn positive_percentage(&self) -> Option<f64> {
+        percentage(
+            self.evaluations.plus + self.evaluations.hashtag,
+            self.evaluations.total(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ScoutingReport {
+    pub team_id: String,
+    pub team_name: String,
+    // Number of the given files the team played in
+    pub matches: usize,
+    // Serve zone x landing zone of the team's serves, most used first
+    pub serve_targets: Vec<ServeCell>,
+    pub combinations_by_rotation: Vec<RotationCombinations>,
+    pub setters: Vec<SetterTendency>,
+    // Side-out first, then transition
+    pub attackers: Vec<PhaseAttackers>,
+    // Lowest share of positive receptions first
+    pub receivers: Vec<ReceiverWeakness>,
+}
+
+fn is_pressure(rally: &Rally, team: TeamSide) -> bool {
+    let late = if rally.set >= 5 { 10 } else { 20 };
+
+    rally.score(team) < rally.score(team.opponent())
+        || rally.home_score >= late
+        || rally.visiting_score >= late
+}
+
+fn player_entry<'a, T>(
+    entries: &'a mut Vec<T>,
+    key: &str,
+    key_of: impl Fn(&T) -> &str,
+    new: impl FnOnce() -> T,
+) -> &'a mut T {
+    let index = match entries.iter().position(|entry| key_of(entry) == key) {
+        Some(index) => index,
+        None => {
+            entries.push(new());
+            entries.len() - 1
+        }
+    };
+
+    &mut entries[index]
+}
+
+// Report on the team `opponent`, matched on `Team::team_id` or case insensitively on
+// `Team::team_name`, from every file it played in. Players are linked across the files with an
+// `IdentityResolver`.
+pub fn scouting_report(scout_files: &[ScoutFile], opponent: &str) -> ScoutingReport {
+    scouting_report_with(scout_files, opponent, &mut IdentityResolver::default())
+}
+
+// Like `scouting_report`, with a resolver that can carry overrides and identities from earlier
+// files
+pub fn scouting_report_with(
+    scout_files: &[ScoutFile],
+    opponent: &str,
+    resolver: &mut IdentityResolver,
+) -> ScoutingReport {
+    let mut report = ScoutingReport {
+        team_id: String::new(),
+        team_name: String::new(),
+        matches: 0,
+        serve_targets: Vec::new(),
+        combinations_by_rotation: (1..=6)
+            .map(|rotation| RotationCombinations {
+                rotation,
+                combinations: Vec::new(),
+            })
+            .collect(),
+        setters: Vec::new(),
+        attackers: [AttackPhase::SideOut, AttackPhase::Transition]
+            .into_iter()
+            .map(|phase| PhaseAttackers {
+                phase,
+                attackers: Vec::new(),
+            })
+            .collect(),
+        receivers: Vec::new(),
+    };
+
+    for scout_file in scout_files {
+        let side = match TeamSide::ALL.into_iter().find(|&side| {
+            let team = scout_file.team(side);
+            team.team_id == opponent || team.team_name.eq_ignore_ascii_case(opponent)
+        }) {
+            Some(side) => side,
+            None => continue,
+        };
+
+        let keys = resolver.resolve_file(scout_file);
+        let key_of = |player_number: u8| {
+            keys.get(&(side, player_number))
+                .cloned()
+                .unwrap_or_else(|| format!("#{}", player_number))
+        };
+
+        report.matches += 1;
+        report.team_id = scout_file.team(side).team_id.clone();
+        report.team_name = scout_file.team(side).team_name.clone();
+
+        for matrix in serve_matrices(scout_file)
+            .into_iter()
+            .filter(|matrix| matrix.team == side)
+        {
+            for cell in matrix.cells {
+                match report.serve_targets.iter_mut().find(|own| {
+                    own.start_zone == cell.start_zone
+                        && own.end_zone == cell.end_zone
+                        && own.end_subzone == cell.end_subzone
+                }) {
+                    Some(own) => own.evaluations.merge(&cell.evaluations),
+                    None => report.serve_targets.push(cell),
+                }
+            }
+        }
+
+        for matrix in reception_matrices(scout_file)
+            .into_iter()
+            .filter(|matrix| matrix.team == side)
+        {
+            for cell in matrix.cells {
+                let key = key_of(cell.player_number);
+                let receiver = player_entry(
+                    &mut report.receivers,
+                    &key,
+                    |receiver| &receiver.key,
+                    || ReceiverWeakness {
+                        key: key.clone(),
+                        player_number: cell.player_number,
+                        player: None,
+                        evaluations: EvaluationCounts::default(),
+                        by_zone: Vec::new(),
+                        weakest_zone: None,
+                    },
+                );
+
+                receiver.player_number = cell.player_number;
+
+                if let Some(player) = scout_file.player(side, cell.player_number) {
+                    receiver.player = Some(player.clone());
+                }
+
+                receiver.evaluations.merge(&cell.evaluations);
+
+                match receiver
+                    .by_zone
+                    .iter_mut()
+                    .find(|zone| zone.zone == cell.end_zone)
+                {
+                    Some(zone) => zone.evaluations.merge(&cell.evaluations),
+                    None => receiver.by_zone.push(ZoneReceptions {
+                        zone: cell.end_zone,
+                        evaluations: cell.evaluations,
+                    }),
+                }
+            }
+        }
+
+        for rally in scout_file.rallies() {
+            let pressure = is_pressure(&rally, side);
+
+            for possession in rally
+                .possessions()
+                .into_iter()
+                .filter(|possession| possession.team == side)
+            {
+                let phase = match possession.reception() {
+                    Some(_) => AttackPhase::SideOut,
+                    None => AttackPhase::Transition,
+                };
+
+                for (index, attack) in possession.attacks() {
+                    if let Some(rotation @ 1..=6) = rally.setter_position(side) {
+                        group_entry(
+                            &mut report.combinations_by_rotation[rotation as usize - 1]
+                                .combinations,
+                            attack.attack_combination().map(str::to_string),
+                        )
+                        .add(attack);
+                    }
+
+                    let key = key_of(attack.player_number);
+                    let attacker = player_entry(
+                        &mut report.attackers[phase as usize].attackers,
+                        &key,
+                        |attacker| &attacker.key,
+                        || AttackerStatistics {
+                            key: key.clone(),
+                            player_number: attack.player_number,
+                            player: None,
+                            statistics: AttackStatistics::default(),
+                        },
+                    );
+                    attacker.player_number = attack.player_number;
+
+                    if let Some(player) = scout_file.player(side, attack.player_number) {
+                        attacker.player = Some(player.clone());
+                    }
+
+                    attacker.statistics.add(attack);
+
+                    let setter_number = match credited_setter(&rally, &possession, index) {
+                        Some(setter_number) => setter_number,
+                        None => continue,
+                    };
+
+                    let key = key_of(setter_number);
+                    let setter = player_entry(
+                        &mut report.setters,
+                        &key,
+                        |setter| &setter.key,
+                        || SetterTendency {
+                            key: key.clone(),
+                            setter_number,
+                            player: None,
+                            overall: Distribution::default(),
+                            under_pressure: Distribution::default(),
+                        },
+                    );
+                    setter.setter_number = setter_number;
+
+                    if let Some(player) = scout_file.player(side, setter_number) {
+                        setter.player = Some(player.clone());
+                    }
+
+                    setter.overall.add(attack);
+
+                    if pressure {
+                        setter.under_pressure.add(attack);
+                    }
+                }
+            }
+        }
+    }
+
+    report
+        .serve_targets
+        .sort_by_key(|cell| std::cmp::Reverse(cell.evaluations.total()));
+
+    for rotation in &mut report.combinations_by_rotation {
+        rotation
+            .combinations
+            .sort_by_key(|group| std::cmp::Reverse(group.statistics.attempts()));
+    }
+
+    report
+        .setters
+        .sort_by_key(|setter| std::cmp::Reverse(setter.overall.sets()));
+
+    for phase in &mut report.attackers {
+        phase.attackers.sort_by_key(|attacker| {
+            std::cmp::Reverse((attacker.statistics.kills(), attacker.statistics.attempts()))
+        });
+    }
+
+    for receiver in &mut report.receivers {
+        receiver.weakest_zone = receiver
+            .by_zone
+            .iter()
+            .filter(|zone| zone.zone.is_some() && zone.evaluations.total() >= MIN_ZONE_RECEPTIONS)
+            .min_by(|a, b| {
+                positive_share(&a.evaluations).total_cmp(&positive_share(&b.evaluations))
+            })
+            .and_then(|zone| zone.zone);
+    }
+
+    report
+        .receivers
+        .sort_by(|a, b| positive_share(&a.evaluations).total_cmp(&positive_share(&b.evaluations)));
+
+    report
+}
+
+fn positive_share(evaluations: &EvaluationCounts) -> f64 {
+    percentage(evaluations.plus + evaluations.hashtag, evaluations.total()).unwrap_or(0.0)
+}
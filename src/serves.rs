@@ -0,0 +1,154 @@
+use crate::data_formats::{Player, ScoutFile, ServeStyle, Skill, TeamSide};
+use crate::statistics::{percentage, EvaluationCounts};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServeStyleStatistics {
+    pub style: ServeStyle,
+    pub serves: EvaluationCounts,
+    // Evaluations of the opponent receptions of these serves
+    pub receptions: EvaluationCounts,
+}
+
+impl ServeStyleStatistics {
+    pub fn ace_percentage(&self) -> Option<f64> {
+        percentage(self.serves.hashtag, self.serves.total())
+    }
+
+    pub fn error_percentage(&self) -> Option<f64> {
+        percentage(self.serves.equal, self.serves.total())
+    }
+
+    // Share of `+` and `#` receptions, lower is better for the server
+    pub fn positive_reception_percentage(&self) -> Option<f64> {
+        percentage(
+            self.receptions.plus + self.receptions.hashtag,
+            self.receptions.total(),
+        )
+    }
+
+    pub fn perfect_reception_percentage(&self) -> Option<f64> {
+        percentage(self.receptions.hashtag, self.receptions.total())
+    }
+}
+
+fn style_entry(
+    styles: &mut Vec<ServeStyleStatistics>,
+    style: ServeStyle,
+) -> &mut ServeStyleStatistics {
+    let index = match styles
+        .iter()
+        .position(|statistics| statistics.style == style)
+    {
+        Some(index) => index,
+        None => {
+            styles.push(ServeStyleStatistics {
+                style,
+                serves: EvaluationCounts::default(),
+                receptions: EvaluationCounts::default(),
+            });
+            styles.len() - 1
+        }
+    };
+
+    &mut styles[index]
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerServeStyles {
+    pub player_number: u8,
+    pub player: Option<Player>,
+    pub styles: Vec<ServeStyleStatistics>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServeStyleReport {
+    pub team: TeamSide,
+    // In the order of `ServeStyle::ALL`, styles the team did not serve are left out
+    pub styles: Vec<ServeStyleStatistics>,
+    pub players: Vec<PlayerServeStyles>,
+}
+
+impl ServeStyleReport {
+    pub fn style(&self, style: ServeStyle) -> Option<&ServeStyleStatistics> {
+        self.styles
+            .iter()
+            .find(|statistics| statistics.style == style)
+    }
+}
+
+// Serves of both teams by serve style, home first. The reception is the touch right after the
+// serve when it is a reception of the other team.
+pub fn serve_style_statistics(scout_file: &ScoutFile) -> Vec<ServeStyleReport> {
+    let mut reports = TeamSide::ALL
+        .into_iter()
+        .map(|team| ServeStyleReport {
+            team,
+            styles: Vec::new(),
+            players: Vec::new(),
+        })
+        .collect::<Vec<ServeStyleReport>>();
+
+    for rally in scout_file.rallies() {
+        let mut touches = rally.touches();
+
+        let serve = match touches.next() {
+            Some(serve) => serve,
+            None => continue,
+        };
+
+        let style = match serve.serve_style() {
+            Some(style) => style,
+            None => continue,
+        };
+
+        let reception = touches
+            .next()
+            .filter(|touch| touch.skill == Skill::Reception && touch.team != serve.team);
+
+        let report = &mut reports[serve.team as usize];
+
+        let index = match report
+            .players
+            .iter()
+            .position(|player| player.player_number == serve.player_number)
+        {
+            Some(index) => index,
+            None => {
+                report.players.push(PlayerServeStyles {
+                    player_number: serve.player_number,
+                    player: scout_file.player(serve.team, serve.player_number).cloned(),
+                    styles: Vec::new(),
+                });
+                report.players.len() - 1
+            }
+        };
+
+        for styles in [&mut report.styles, &mut report.players[index].styles] {
+            let statistics = style_entry(styles, style);
+            statistics.serves.add(serve.evaluation);
+
+            if let Some(reception) = reception {
+                statistics.receptions.add(reception.evaluation);
+            }
+        }
+    }
+
+    for report in &mut reports {
+        let order = |statistics: &ServeStyleStatistics| {
+            ServeStyle::ALL
+                .iter()
+                .position(|style| *style == statistics.style)
+        };
+
+        report.styles.sort_by_key(order);
+
+        for player in &mut report.players {
+            player.styles.sort_by_key(order);
+        }
+    }
+
+    reports
+}
@@ -0,0 +1,229 @@
+use crate::attacks::AttackStatistics;
+use crate::data_formats::{Evaluation, ManualCodeExplanation, Player, ScoutFile};
+use crate::rally::{Possession, Rally, ScoreSituation};
+use crate::statistics::percentage;
+
+// Where a set went, the attack combination when one was scouted and the start zone otherwise
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum SetDestination {
+    Combination(String),
+    Zone(u8),
+    Unknown,
+}
+
+impl SetDestination {
+    pub fn of(attack: &ManualCodeExplanation) -> SetDestination {
+        match (attack.attack_combination(), attack.start_zone) {
+            (Some(combination), _) => SetDestination::Combination(combination.to_string()),
+            (None, Some(zone)) => SetDestination::Zone(zone),
+            (None, None) => SetDestination::Unknown,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DestinationShare {
+    pub destination: SetDestination,
+    pub sets: u32,
+    // The attacks that followed the sets to this destination
+    pub attacks: AttackStatistics,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Distribution {
+    pub destinations: Vec<DestinationShare>,
+}
+
+impl Distribution {
+    pub fn add(&mut self, attack: &ManualCodeExplanation) {
+        let destination = SetDestination::of(attack);
+
+        let index = match self
+            .destinations
+            .iter()
+            .position(|share| share.destination == destination)
+        {
+            Some(index) => index,
+            None => {
+                self.destinations.push(DestinationShare {
+                    destination,
+                    sets: 0,
+                    attacks: AttackStatistics::default(),
+                });
+                self.destinations.len() - 1
+            }
+        };
+
+        self.destinations[index].sets += 1;
+        self.destinations[index].attacks.add(attack);
+    }
+
+    pub fn merge(&mut self, other: &Distribution) {
+        for share in &other.destinations {
+            match self
+                .destinations
+                .iter_mut()
+                .find(|own| own.destination == share.destination)
+            {
+                Some(own) => {
+                    own.sets += share.sets;
+                    own.attacks.merge(&share.attacks);
+                }
+                None => self.destinations.push(share.clone()),
+            }
+        }
+    }
+
+    pub fn sets(&self) -> u32 {
+        self.destinations.iter().map(|share| share.sets).sum()
+    }
+
+    // Share of the sets going to the destination, from 0 to 100
+    pub fn percentage(&self, destination: &SetDestination) -> Option<f64> {
+        let sets = self
+            .destinations
+            .iter()
+            .find(|share| &share.destination == destination)
+            .map(|share| share.sets)
+            .unwrap_or(0);
+
+        percentage(sets, self.sets())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistributionSplit<K> {
+    pub key: K,
+    pub distribution: Distribution,
+}
+
+fn split_entry<K: PartialEq>(splits: &mut Vec<DistributionSplit<K>>, key: K) -> &mut Distribution {
+    let index = match splits.iter().position(|split| split.key == key) {
+        Some(index) => index,
+        None => {
+            splits.push(DistributionSplit {
+                key,
+                distribution: Distribution::default(),
+            });
+            splits.len() - 1
+        }
+    };
+
+    &mut splits[index].distribution
+}
+
+fn merge_splits<K: PartialEq + Clone>(
+    splits: &mut Vec<DistributionSplit<K>>,
+    other: &[DistributionSplit<K>],
+) {
+    for split in other {
+        split_entry(splits, split.key.clone()).merge(&split.distribution);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetterDistribution {
+    // Setters are told apart by team id and shirt number so files can be combined
+    pub team_id: String,
+    pub setter_number: u8,
+    pub player: Option<Player>,
+    pub overall: Distribution,
+    // Keyed by the setter position of the team
+    pub by_rotation: Vec<DistributionSplit<u8>>,
+    // Keyed by the reception of the possession, None for transition
+    pub by_reception: Vec<DistributionSplit<Option<Evaluation>>>,
+    pub by_score: Vec<DistributionSplit<ScoreSituation>>,
+}
+
+impl SetterDistribution {
+    pub fn merge(&mut self, other: &SetterDistribution) {
+        self.overall.merge(&other.overall);
+        merge_splits(&mut self.by_rotation, &other.by_rotation);
+        merge_splits(&mut self.by_reception, &other.by_reception);
+        merge_splits(&mut self.by_score, &other.by_score);
+    }
+}
+
+// Shirt number of the setter credited with the attack at `index` of the possession, the player
+// who set it, or the setter on court from the `P` codes when the set itself was not scouted
+pub(crate) fn credited_setter(rally: &Rally, possession: &Possession, index: usize) -> Option<u8> {
+    possession
+        .set_before(index)
+        .map(|set| set.player_number)
+        .or(rally.setter(possession.team))
+}
+
+// Attacks are credited to the setter from `credited_setter`
+pub fn setter_distribution(scout_file: &ScoutFile) -> Vec<SetterDistribution> {
+    let mut distributions: Vec<SetterDistribution> = Vec::new();
+
+    for rally in scout_file.rallies() {
+        for possession in rally.possessions() {
+            let team = possession.team;
+            let reception = possession.reception().map(|reception| reception.evaluation);
+
+            for (index, attack) in possession.attacks() {
+                let setter_number = match credited_setter(&rally, &possession, index) {
+                    Some(setter_number) => setter_number,
+                    None => continue,
+                };
+
+                let team_id = &scout_file.team(team).team_id;
+
+                let distribution = match distributions.iter().position(|distribution| {
+                    &distribution.team_id == team_id && distribution.setter_number == setter_number
+                }) {
+                    Some(index) => &mut distributions[index],
+                    None => {
+                        distributions.push(SetterDistribution {
+                            team_id: team_id.clone(),
+                            setter_number,
+                            player: scout_file.player(team, setter_number).cloned(),
+                            overall: Distribution::default(),
+                            by_rotation: Vec::new(),
+                            by_reception: Vec::new(),
+                            by_score: Vec::new(),
+                        });
+                        distributions.last_mut().unwrap()
+                    }
+                };
+
+                distribution.overall.add(attack);
+
+                if let Some(rotation) = rally.setter_position(team) {
+                    split_entry(&mut distribution.by_rotation, rotation).add(attack);
+                }
+
+                split_entry(&mut distribution.by_reception, reception).add(attack);
+                split_entry(&mut distribution.by_score, rally.score_situation(team)).add(attack);
+            }
+        }
+    }
+
+    distributions
+}
+
+// Combines the distributions of several files, e.g. every match of a season
+pub fn setter_distribution_for_files(scout_files: &[ScoutFile]) -> Vec<SetterDistribution> {
+    let mut distributions: Vec<SetterDistribution> = Vec::new();
+
+    for scout_file in scout_files {
+        for distribution in setter_distribution(scout_file) {
+            match distributions.iter_mut().find(|own| {
+                own.team_id == distribution.team_id
+                    && own.setter_number == distribution.setter_number
+            }) {
+                Some(own) => own.merge(&distribution),
+                None => distributions.push(distribution),
+            }
+        }
+    }
+
+    distributions
+}
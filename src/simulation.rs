@@ -0,0 +1,485 @@
+use crate::data_formats::{Evaluation, ScoutFile, Skill, TeamSide};
+
+// Rotation specific counts are used once a state was left at least this many times in the rotation,
+// the counts over every rotation otherwise
+const MIN_ROTATION_COUNT: u32 = 10;
+
+// Rallies longer than this are decided by a coin flip, in case two models keep passing the ball
+const MAX_TOUCHES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TouchState {
+    pub skill: Skill,
+    pub evaluation: Evaluation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum NextEvent {
+    // Next touch by the same team
+    Touch(TouchState),
+    // Next touch by the opponent, as it was seen in the matches of the model
+    OpponentTouch(TouchState),
+    // Rally over, won or lost by the team of the model
+    Won,
+    Lost,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EventCount {
+    pub event: NextEvent,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateTransitions {
+    // Setter position of the team, None for the counts over every rotation
+    pub rotation: Option<u8>,
+    // None for the start of a rally served by the team
+    pub from: Option<TouchState>,
+    pub events: Vec<EventCount>,
+}
+
+impl StateTransitions {
+    pub fn total(&self) -> u32 {
+        self.events.iter().map(|event| event.count).sum()
+    }
+
+    pub fn probability(&self, event: NextEvent) -> Option<f64> {
+        let count = self
+            .events
+            .iter()
+            .find(|count| count.event == event)
+            .map_or(0, |count| count.count);
+
+        match self.total() {
+            0 => None,
+            total => Some(count as f64 / total as f64),
+        }
+    }
+
+    fn add(&mut self, event: NextEvent) {
+        match self.events.iter_mut().find(|count| count.event == event) {
+            Some(count) => count.count += 1,
+            None => self.events.push(EventCount { event, count: 1 }),
+        }
+    }
+
+    fn draw(&self, random: &mut Random) -> Option<NextEvent> {
+        let total = self.total();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = random.below(total);
+
+        for count in &self.events {
+            if target < count.count {
+                return Some(count.event);
+            }
+
+            target -= count.count;
+        }
+
+        None
+    }
+}
+
+fn transitions_entry(
+    transitions: &mut Vec<StateTransitions>,
+    rotation: Option<u8>,
+    from: Option<TouchState>,
+) -> &mut StateTransitions {
+    let index = match transitions
+        .iter()
+        .position(|transition| transition.rotation == rotation && transition.from == from)
+    {
+        Some(index) => index,
+        None => {
+            transitions.push(StateTransitions {
+                rotation,
+                from,
+                events: Vec::new(),
+            });
+            transitions.len() - 1
+        }
+    };
+
+    &mut transitions[index]
+}
+
+fn find(
+    transitions: &[StateTransitions],
+    rotation: Option<u8>,
+    from: Option<TouchState>,
+) -> Option<&StateTransitions> {
+    let overall = transitions
+        .iter()
+        .find(|transition| transition.rotation.is_none() && transition.from == from);
+
+    match rotation {
+        Some(rotation) => transitions
+            .iter()
+            .find(|transition| transition.rotation == Some(rotation) && transition.from == from)
+            .filter(|transition| transition.total() >= MIN_ROTATION_COUNT)
+            .or(overall),
+        None => overall,
+    }
+}
+
+fn state(skill: Skill, evaluation: Evaluation) -> TouchState {
+    TouchState { skill, evaluation }
+}
+
+// Markov chain of the rallies of one team. The states are the skill and evaluation of the touches,
+// `transitions` holds what followed a touch of the team and `responses` the touch the team made
+// after a touch of the opponent, so that a simulation takes both teams into account.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamModel {
+    pub team_id: String,
+    pub transitions: Vec<StateTransitions>,
+    pub responses: Vec<StateTransitions>,
+    // Setter position at the first rally of each set, counted per rotation 1 to 6
+    pub starting_rotations: [u32; 6],
+}
+
+impl TeamModel {
+    // Model of `team`, matched on `Team::team_id` or case insensitively on `Team::team_name`, from
+    // every file it played in
+    pub fn from_files(scout_files: &[ScoutFile], team: &str) -> TeamModel {
+        let mut model = TeamModel::default();
+
+        for scout_file in scout_files {
+            if let Some(side) = TeamSide::ALL.into_iter().find(|&side| {
+                let own = scout_file.team(side);
+                own.team_id == team || own.team_name.eq_ignore_ascii_case(team)
+            }) {
+                model.team_id = scout_file.team(side).team_id.clone();
+                model.add_file(scout_file, side);
+            }
+        }
+
+        model
+    }
+
+    pub fn add_file(&mut self, scout_file: &ScoutFile, side: TeamSide) {
+        let mut set = None;
+
+        for rally in scout_file.rallies() {
+            let rotation = rally
+                .setter_position(side)
+                .filter(|rotation| (1..=6).contains(rotation));
+
+            if set != Some(rally.set) {
+                set = Some(rally.set);
+
+                if let Some(rotation) = rotation {
+                    self.starting_rotations[rotation as usize - 1] += 1;
+                }
+            }
+
+            let winner = match rally.winner() {
+                Some(winner) => winner,
+                None => continue,
+            };
+
+            let touches = rally.touches().collect::<Vec<_>>();
+
+            for (index, touch) in touches.iter().enumerate() {
+                let from = state(touch.skill, touch.evaluation);
+                let next = touches.get(index + 1);
+
+                if index == 0 && touch.team == side {
+                    self.add(rotation, None, NextEvent::Touch(from), false);
+                }
+
+                if touch.team == side {
+                    let event = match next {
+                        Some(next) if next.team == side => {
+                            NextEvent::Touch(state(next.skill, next.evaluation))
+                        }
+                        Some(next) => NextEvent::OpponentTouch(state(next.skill, next.evaluation)),
+                        None if winner == side => NextEvent::Won,
+                        None => NextEvent::Lost,
+                    };
+
+                    self.add(rotation, Some(from), event, false);
+                } else if let Some(next) = next.filter(|next| next.team == side) {
+                    self.add(
+                        rotation,
+                        Some(from),
+                        NextEvent::Touch(state(next.skill, next.evaluation)),
+                        true,
+                    );
+                }
+            }
+        }
+    }
+
+    fn add(
+        &mut self,
+        rotation: Option<u8>,
+        from: Option<TouchState>,
+        event: NextEvent,
+        response: bool,
+    ) {
+        let transitions = match response {
+            true => &mut self.responses,
+            false => &mut self.transitions,
+        };
+
+        transitions_entry(transitions, None, from).add(event);
+
+        if rotation.is_some() {
+            transitions_entry(transitions, rotation, from).add(event);
+        }
+    }
+
+    // What follows a touch of the team, None for the serve at the start of a rally
+    pub fn transition(
+        &self,
+        rotation: Option<u8>,
+        from: Option<TouchState>,
+    ) -> Option<&StateTransitions> {
+        find(&self.transitions, rotation, from)
+    }
+
+    // Touch of the team after the opponent touch `from`
+    pub fn response(&self, rotation: Option<u8>, from: TouchState) -> Option<&StateTransitions> {
+        find(&self.responses, rotation, Some(from))
+    }
+
+    // Most frequent setter position at the start of a set, 1 without data
+    pub fn starting_rotation(&self) -> u8 {
+        (1..=6)
+            .rev()
+            .max_by_key(|rotation| self.starting_rotations[*rotation as usize - 1])
+            .unwrap_or(1)
+    }
+}
+
+// Setter position after a side-out, the setter moves from zone 1 to 6, 6 to 5 and so on
+pub fn next_rotation(rotation: u8) -> u8 {
+    match rotation {
+        1 => 6,
+        rotation => rotation - 1,
+    }
+}
+
+// Small xorshift generator so that simulations are repeatable from a seed without dependencies
+#[derive(Debug, Clone, PartialEq)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        // Spread the seed so that 0 and small seeds are usable
+        let mut state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        state ^= state >> 31;
+
+        Random {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // Between 0 (inclusive) and 1 (exclusive)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Between 0 (inclusive) and `bound` (exclusive)
+    pub fn below(&mut self, bound: u32) -> u32 {
+        (self.next_f64() * bound as f64) as u32
+    }
+
+    pub fn coin(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+}
+
+// Simulates one rally served by `server`, returns true when the server wins it
+pub fn simulate_rally(
+    server: &TeamModel,
+    server_rotation: u8,
+    receiver: &TeamModel,
+    receiver_rotation: u8,
+    random: &mut Random,
+) -> bool {
+    let teams = [(server, server_rotation), (receiver, receiver_rotation)];
+    // Index in `teams` of the team of the last touch
+    let mut current = 0;
+    let mut from = None;
+
+    for _ in 0..MAX_TOUCHES {
+        let (model, rotation) = teams[current];
+
+        let event = match model
+            .transition(Some(rotation), from)
+            .and_then(|transitions| transitions.draw(random))
+        {
+            Some(event) => event,
+            None => return random.coin(),
+        };
+
+        match event {
+            NextEvent::Touch(touch) => from = Some(touch),
+            NextEvent::OpponentTouch(touch) => {
+                let (opponent, rotation) = teams[1 - current];
+                let response = from
+                    .and_then(|from| opponent.response(Some(rotation), from))
+                    .and_then(|responses| responses.draw(random));
+
+                from = match response {
+                    Some(NextEvent::Touch(response)) => Some(response),
+                    _ => Some(touch),
+                };
+                current = 1 - current;
+            }
+            NextEvent::Won => return current == 0,
+            NextEvent::Lost => return current == 1,
+        }
+    }
+
+    random.coin()
+}
+
+// Simulates a set to 25 (15 in the fifth set) with a two point lead, returns the points of `team`
+// and of `opponent`
+pub fn simulate_set(
+    team: &TeamModel,
+    opponent: &TeamModel,
+    team_serves: bool,
+    set_number: u8,
+    random: &mut Random,
+) -> (u8, u8) {
+    let target = if set_number >= 5 { 15 } else { 25 };
+    let mut scores = [0u8, 0u8];
+    let mut rotations = [team.starting_rotation(), opponent.starting_rotation()];
+    let models = [team, opponent];
+    let mut server = if team_serves { 0 } else { 1 };
+
+    while (scores[0] < target && scores[1] < target) || scores[0].abs_diff(scores[1]) < 2 {
+        let receiver = 1 - server;
+        let server_wins = simulate_rally(
+            models[server],
+            rotations[server],
+            models[receiver],
+            rotations[receiver],
+            random,
+        );
+
+        if server_wins {
+            scores[server] += 1;
+        } else {
+            scores[receiver] += 1;
+            rotations[receiver] = next_rotation(rotations[receiver]);
+            server = receiver;
+        }
+    }
+
+    (scores[0], scores[1])
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchResult {
+    pub sets_won: u8,
+    pub sets_lost: u8,
+    pub probability: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prediction {
+    pub simulations: u32,
+    // Share of the simulated sets won by the team, between 0 and 1
+    pub set_win_probability: f64,
+    pub match_win_probability: f64,
+    // Final results in sets from the team's point of view, 3-0 first
+    pub results: Vec<MatchResult>,
+}
+
+// Plays `simulations` best of five matches between the models. The first serve of the match
+// alternates between simulations and the serve of the fifth set is drawn.
+pub fn predict(team: &TeamModel, opponent: &TeamModel, simulations: u32, seed: u64) -> Prediction {
+    let mut random = Random::new(seed);
+    let mut sets = [0u32, 0u32];
+    let mut results = [[0u32; 3]; 2];
+
+    for simulation in 0..simulations {
+        let mut won = 0;
+        let mut lost = 0;
+        let mut set_number = 1;
+
+        while won < 3 && lost < 3 {
+            let team_serves = match set_number {
+                5 => random.coin(),
+                _ => (simulation + set_number as u32) % 2 == 1,
+            };
+            let (points, opponent_points) =
+                simulate_set(team, opponent, team_serves, set_number, &mut random);
+
+            if points > opponent_points {
+                won += 1;
+            } else {
+                lost += 1;
+            }
+
+            set_number += 1;
+        }
+
+        sets[0] += won;
+        sets[1] += lost;
+
+        match won {
+            3 => results[0][lost as usize] += 1,
+            _ => results[1][won as usize] += 1,
+        }
+    }
+
+    let share = |count: u32, total: u32| match total {
+        0 => 0.0,
+        total => count as f64 / total as f64,
+    };
+
+    let mut match_results = Vec::new();
+
+    for (lost, count) in results[0].iter().enumerate() {
+        match_results.push(MatchResult {
+            sets_won: 3,
+            sets_lost: lost as u8,
+            probability: share(*count, simulations),
+        });
+    }
+
+    for (won, count) in results[1].iter().enumerate().rev() {
+        match_results.push(MatchResult {
+            sets_won: won as u8,
+            sets_lost: 3,
+            probability: share(*count, simulations),
+        });
+    }
+
+    Prediction {
+        simulations,
+        set_win_probability: share(sets[0], sets[0] + sets[1]),
+        match_win_probability: share(results[0].iter().sum(), simulations),
+        results: match_results,
+    }
+}
@@ -0,0 +1,372 @@
+use crate::data_formats::{Evaluation, ManualCodeExplanation, Player, ScoutFile, Skill, TeamSide};
+use crate::rally::{PointOrigin, Rally};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvaluationCounts {
+    pub equal: u32,
+    pub slash: u32,
+    pub minus: u32,
+    pub exclamation: u32,
+    pub plus: u32,
+    pub hashtag: u32,
+}
+
+impl EvaluationCounts {
+    pub fn get(&self, evaluation: Evaluation) -> u32 {
+        match evaluation {
+            Evaluation::Equal => self.equal,
+            Evaluation::Slash => self.slash,
+            Evaluation::Minus => self.minus,
+            Evaluation::Exclamation => self.exclamation,
+            Evaluation::Plus => self.plus,
+            Evaluation::Hashtag => self.hashtag,
+        }
+    }
+
+    pub fn add(&mut self, evaluation: Evaluation) {
+        match evaluation {
+            Evaluation::Equal => self.equal += 1,
+            Evaluation::Slash => self.slash += 1,
+            Evaluation::Minus => self.minus += 1,
+            Evaluation::Exclamation => self.exclamation += 1,
+            Evaluation::Plus => self.plus += 1,
+            Evaluation::Hashtag => self.hashtag += 1,
+        }
+    }
+
+    pub fn merge(&mut self, other: &EvaluationCounts) {
+        self.equal += other.equal;
+        self.slash += other.slash;
+        self.minus += other.minus;
+        self.exclamation += other.exclamation;
+        self.plus += other.plus;
+        self.hashtag += other.hashtag;
+    }
+
+    pub fn total(&self) -> u32 {
+        self.equal + self.slash + self.minus + self.exclamation + self.plus + self.hashtag
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SkillCounts {
+    pub serve: EvaluationCounts,
+    pub reception: EvaluationCounts,
+    pub attack: EvaluationCounts,
+    pub block: EvaluationCounts,
+    pub dig: EvaluationCounts,
+    pub set: EvaluationCounts,
+    pub free_ball: EvaluationCounts,
+}
+
+impl SkillCounts {
+    pub fn get(&self, skill: Skill) -> &EvaluationCounts {
+        match skill {
+            Skill::Serve => &self.serve,
+            Skill::Reception => &self.reception,
+            Skill::Attack => &self.attack,
+            Skill::Block => &self.block,
+            Skill::Dig => &self.dig,
+            Skill::Set => &self.set,
+            Skill::FreeBall => &self.free_ball,
+        }
+    }
+
+    pub fn get_mut(&mut self, skill: Skill) -> &mut EvaluationCounts {
+        match skill {
+            Skill::Serve => &mut self.serve,
+            Skill::Reception => &mut self.reception,
+            Skill::Attack => &mut self.attack,
+            Skill::Block => &mut self.block,
+            Skill::Dig => &mut self.dig,
+            Skill::Set => &mut self.set,
+            Skill::FreeBall => &mut self.free_ball,
+        }
+    }
+
+    pub fn add(&mut self, code: &ManualCodeExplanation) {
+        self.get_mut(code.skill).add(code.evaluation);
+    }
+
+    pub fn merge(&mut self, other: &SkillCounts) {
+        for skill in Skill::ALL {
+            self.get_mut(skill).merge(other.get(skill));
+        }
+    }
+
+    pub fn serves(&self) -> u32 {
+        self.serve.total()
+    }
+
+    pub fn aces(&self) -> u32 {
+        self.serve.hashtag
+    }
+
+    pub fn serve_errors(&self) -> u32 {
+        self.serve.equal
+    }
+
+    pub fn receptions(&self) -> u32 {
+        self.reception.total()
+    }
+
+    pub fn reception_errors(&self) -> u32 {
+        self.reception.equal
+    }
+
+    // Share of receptions graded `+` or `#`, from 0 to 100
+    pub fn reception_positive_percentage(&self) -> Option<f64> {
+        percentage(
+            self.reception.plus + self.reception.hashtag,
+            self.reception.total(),
+        )
+    }
+
+    // Share of receptions graded `#`, from 0 to 100
+    pub fn reception_perfect_percentage(&self) -> Option<f64> {
+        percentage(self.reception.hashtag, self.reception.total())
+    }
+
+    pub fn attacks(&self) -> u32 {
+        self.attack.total()
+    }
+
+    pub fn kills(&self) -> u32 {
+        self.attack.hashtag
+    }
+
+    pub fn attack_errors(&self) -> u32 {
+        self.attack.equal
+    }
+
+    pub fn attacks_blocked(&self) -> u32 {
+        self.attack.slash
+    }
+
+    pub fn kill_percentage(&self) -> Option<f64> {
+        percentage(self.kills(), self.attacks())
+    }
+
+    // (kills - errors - blocked) / attempts, from -100 to 100
+    pub fn attack_efficiency(&self) -> Option<f64> {
+        efficiency(
+            self.kills(),
+            self.attack_errors() + self.attacks_blocked(),
+            self.attacks(),
+        )
+    }
+
+    pub fn block_points(&self) -> u32 {
+        self.block.hashtag
+    }
+
+    // Digs that kept the ball in play
+    pub fn digs(&self) -> u32 {
+        self.dig.total() - self.dig.equal
+    }
+
+    pub fn points(&self) -> u32 {
+        self.aces() + self.kills() + self.block_points()
+    }
+}
+
+pub(crate) fn percentage(part: u32, total: u32) -> Option<f64> {
+    if total == 0 {
+        None
+    } else {
+        Some(part as f64 * 100.0 / total as f64)
+    }
+}
+
+pub(crate) fn efficiency(positive: u32, negative: u32, total: u32) -> Option<f64> {
+    if total == 0 {
+        None
+    } else {
+        Some((positive as f64 - negative as f64) * 100.0 / total as f64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerStatistics {
+    pub team: TeamSide,
+    pub player_number: u8,
+    // None when the shirt number is not on the roster
+    pub player: Option<Player>,
+    pub skills: SkillCounts,
+}
+
+// Box score of every rostered player, followed by the players that only appear in codes
+pub fn player_statistics(scout_file: &ScoutFile) -> Vec<PlayerStatistics> {
+    let mut statistics = Vec::new();
+
+    for team in TeamSide::ALL {
+        for player in scout_file.players(team) {
+            statistics.push(PlayerStatistics {
+                team,
+                player_number: player.player_number,
+                player: Some(player.clone()),
+                skills: SkillCounts::default(),
+            });
+        }
+    }
+
+    for code in scout_file.touches() {
+        let index = statistics
+            .iter()
+            .position(|player| {
+                player.team == code.team && player.player_number == code.player_number
+            })
+            .unwrap_or_else(|| {
+                statistics.push(PlayerStatistics {
+                    team: code.team,
+                    player_number: code.player_number,
+                    player: None,
+                    skills: SkillCounts::default(),
+                });
+
+                statistics.len() - 1
+            });
+
+        statistics[index].skills.add(code);
+    }
+
+    statistics
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamStatistics {
+    pub rallies: u32,
+    pub points_won: u32,
+    pub points_lost: u32,
+    pub serve_points: u32,
+    pub attack_points: u32,
+    pub block_points: u32,
+    pub opponent_error_points: u32,
+    pub other_points: u32,
+    // Rallies received and won while receiving
+    pub side_out_attempts: u32,
+    pub side_outs: u32,
+    // Rallies served and won while serving
+    pub break_point_attempts: u32,
+    pub break_points: u32,
+    pub skills: SkillCounts,
+}
+
+impl TeamStatistics {
+    pub fn add_rally(&mut self, team: TeamSide, rally: &Rally) {
+        self.rallies += 1;
+
+        for touch in rally.touches().filter(|touch| touch.team == team) {
+            self.skills.add(touch);
+        }
+
+        let winner = match rally.winner() {
+            Some(winner) => winner,
+            None => return,
+        };
+
+        let won = winner == team;
+
+        if won {
+            self.points_won += 1;
+
+            match rally.point_origin() {
+                Some(PointOrigin::Serve) => self.serve_points += 1,
+                Some(PointOrigin::Attack) => self.attack_points += 1,
+                Some(PointOrigin::Block) => self.block_points += 1,
+                Some(PointOrigin::OpponentError) => self.opponent_error_points += 1,
+                Some(PointOrigin::Other) | None => self.other_points += 1,
+            }
+        } else {
+            self.points_lost += 1;
+        }
+
+        match rally.serving_team() {
+            Some(serving) if serving == team => {
+                self.break_point_attempts += 1;
+                self.break_points += won as u32;
+            }
+            Some(_) => {
+                self.side_out_attempts += 1;
+                self.side_outs += won as u32;
+            }
+            None => {}
+        }
+    }
+
+    pub fn merge(&mut self, other: &TeamStatistics) {
+        self.rallies += other.rallies;
+        self.points_won += other.points_won;
+        self.points_lost += other.points_lost;
+        self.serve_points += other.serve_points;
+        self.attack_points += other.attack_points;
+        self.block_points += other.block_points;
+        self.opponent_error_points += other.opponent_error_points;
+        self.other_points += other.other_points;
+        self.side_out_attempts += other.side_out_attempts;
+        self.side_outs += other.side_outs;
+        self.break_point_attempts += other.break_point_attempts;
+        self.break_points += other.break_points;
+        self.skills.merge(&other.skills);
+    }
+
+    pub fn side_out_percentage(&self) -> Option<f64> {
+        percentage(self.side_outs, self.side_out_attempts)
+    }
+
+    pub fn break_point_percentage(&self) -> Option<f64> {
+        percentage(self.break_points, self.break_point_attempts)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetStatistics {
+    pub set_number: u8,
+    pub statistics: TeamStatistics,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TeamReport {
+    pub team: TeamSide,
+    pub match_statistics: TeamStatistics,
+    pub sets: Vec<SetStatistics>,
+}
+
+// One report for the home team and one for the visiting team, built from the rallies
+pub fn team_statistics(scout_file: &ScoutFile) -> Vec<TeamReport> {
+    let rallies = scout_file.rallies();
+
+    TeamSide::ALL
+        .iter()
+        .map(|&team| {
+            let mut report = TeamReport {
+                team,
+                match_statistics: TeamStatistics::default(),
+                sets: Vec::new(),
+            };
+
+            for rally in &rallies {
+                report.match_statistics.add_rally(team, rally);
+
+                if report.sets.last().map(|set| set.set_number) != Some(rally.set) {
+                    report.sets.push(SetStatistics {
+                        set_number: rally.set,
+                        statistics: TeamStatistics::default(),
+                    });
+                }
+
+                if let Some(set) = report.sets.last_mut() {
+                    set.statistics.add_rally(team, rally);
+                }
+            }
+
+            report
+        })
+        .collect()
+}
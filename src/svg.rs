@@ -0,0 +1,313 @@
+use std::fmt::Write;
+
+use crate::court::{
+    CourtCoordinate, CourtHalf, CENTRE_COLUMN, COLUMN_METRES, HALF_LENGTH, HALF_WIDTH, NET_ROW,
+    ROW_METRES,
+};
+use crate::data_formats::Evaluation;
+use crate::heatmap::{Heatmap, Trajectory};
+use crate::zones::{ReceptionMatrix, ServeMatrix};
+
+// Charts are drawn in metres with the centre of the court at the origin, the near half at the
+// bottom and the far half at the top. The view covers the whole Data Volley grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CourtChart {
+    // Width of the image in pixels, the height follows from the proportions of the grid
+    pub width: u32,
+    layers: Vec<String>,
+}
+
+impl CourtChart {
+    pub fn new(width: u32) -> CourtChart {
+        CourtChart {
+            width,
+            layers: Vec::new(),
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        (self.width as f64 * HALF_LENGTH / HALF_WIDTH).round() as u32
+    }
+
+    // Shades one zone of a half, `intensity` goes from 0 (transparent) to 1 (solid)
+    pub fn add_zone(&mut self, half: CourtHalf, zone: u8, intensity: f64, label: &str) {
+        let (x, y, width, height) = match zone_rectangle(half, zone) {
+            Some(rectangle) => rectangle,
+            None => return,
+        };
+
+        let mut layer = String::new();
+
+        let _ = write!(
+            layer,
+            r##"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="#1f77b4" fill-opacity="{:.2}"/>"##,
+            x,
+            y,
+            width,
+            height,
+            intensity.clamp(0.0, 1.0) * 0.8
+        );
+
+        if !label.is_empty() {
+            let _ = write!(
+                layer,
+                r#"<text x="{:.2}" y="{:.2}" font-size="0.6" text-anchor="middle" dominant-baseline="middle">{}</text>"#,
+                x + width / 2.0,
+                y + height / 2.0,
+                escape(label)
+            );
+        }
+
+        self.layers.push(layer);
+    }
+
+    // Every bin as a rectangle shaded by its count relative to the fullest bin
+    pub fn add_heatmap(&mut self, heatmap: &Heatmap) {
+        let max = heatmap.max();
+
+        if max == 0 {
+            return;
+        }
+
+        let mut layer = String::new();
+        let bin_width = 100.0 / heatmap.columns as f64;
+        let bin_height = 100.0 / heatmap.rows as f64;
+
+        for row in 0..heatmap.rows {
+            for column in 0..heatmap.columns {
+                let count = heatmap.get(column, row);
+
+                if count == 0 {
+                    continue;
+                }
+
+                let (left, top) =
+                    to_chart(column as f64 * bin_width, (row + 1) as f64 * bin_height);
+
+                let _ = write!(
+                    layer,
+                    r##"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="#d62728" fill-opacity="{:.2}"/>"##,
+                    left,
+                    top,
+                    bin_width * COLUMN_METRES,
+                    bin_height * ROW_METRES,
+                    count as f64 / max as f64 * 0.8
+                );
+            }
+        }
+
+        self.layers.push(layer);
+    }
+
+    // A line from the start through the mid to the end point with a dot where the ball ends,
+    // coloured by evaluation
+    pub fn add_trajectories(&mut self, trajectories: &[Trajectory]) {
+        let mut layer = String::new();
+
+        for trajectory in trajectories {
+            let points = trajectory
+                .points()
+                .iter()
+                .map(|&coordinate| coordinate_to_chart(coordinate))
+                .collect::<Vec<(f64, f64)>>();
+
+            let colour = evaluation_colour(trajectory.evaluation);
+
+            if points.len() > 1 {
+                let path = points
+                    .iter()
+                    .map(|(x, y)| format!("{:.2},{:.2}", x, y))
+                    .collect::<Vec<String>>()
+                    .join(" ");
+
+                let _ = write!(
+                    layer,
+                    r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="0.08" stroke-opacity="0.7"/>"#,
+                    path, colour
+                );
+            }
+
+            if let Some((x, y)) = points.last() {
+                let _ = write!(
+                    layer,
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="0.15" fill="{}"/>"#,
+                    x, y, colour
+                );
+            }
+        }
+
+        self.layers.push(layer);
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="{:.2} {:.2} {:.2} {:.2}">"#,
+            self.width,
+            self.height(),
+            -HALF_WIDTH,
+            -HALF_LENGTH,
+            HALF_WIDTH * 2.0,
+            HALF_LENGTH * 2.0
+        );
+
+        let _ = write!(
+            svg,
+            r##"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="#e9e4d9"/>"##,
+            -HALF_WIDTH,
+            -HALF_LENGTH,
+            HALF_WIDTH * 2.0,
+            HALF_LENGTH * 2.0
+        );
+        svg.push_str(
+            r##"<rect x="-4.5" y="-9" width="9" height="18" fill="#f4a460" stroke="#ffffff" stroke-width="0.05"/>"##,
+        );
+
+        for layer in &self.layers {
+            svg.push_str(layer);
+        }
+
+        // Attack lines and net on top of the layers so they stay visible
+        svg.push_str(
+            r##"<line x1="-4.5" y1="-3" x2="4.5" y2="-3" stroke="#ffffff" stroke-width="0.05"/>"##,
+        );
+        svg.push_str(
+            r##"<line x1="-4.5" y1="3" x2="4.5" y2="3" stroke="#ffffff" stroke-width="0.05"/>"##,
+        );
+        svg.push_str(
+            r##"<line x1="-5" y1="0" x2="5" y2="0" stroke="#333333" stroke-width="0.1"/>"##,
+        );
+        svg.push_str("</svg>");
+
+        svg
+    }
+}
+
+// Grid position to chart position, the chart's y axis points down
+fn to_chart(x: f64, y: f64) -> (f64, f64) {
+    (
+        (x - CENTRE_COLUMN) * COLUMN_METRES,
+        -(y - NET_ROW) * ROW_METRES,
+    )
+}
+
+fn coordinate_to_chart(coordinate: CourtCoordinate) -> (f64, f64) {
+    (coordinate.x_metres(), -coordinate.y_metres())
+}
+
+// Left, top, width and height of a zone with the same bounds as `CourtCoordinate::zone`
+fn zone_rectangle(half: CourtHalf, zone: u8) -> Option<(f64, f64, f64, f64)> {
+    let (row, column) = match zone {
+        2 => (0, 0),
+        3 => (0, 1),
+        4 => (0, 2),
+        9 => (1, 0),
+        8 => (1, 1),
+        7 => (1, 2),
+        1 => (2, 0),
+        6 => (2, 1),
+        5 => (2, 2),
+        _ => return None,
+    };
+
+    let (near, far) = [(0.0, 3.0), (3.0, 6.0), (6.0, HALF_LENGTH)][row];
+    // Lateral bounds to the right of the centre line for the team defending the half
+    let (right, left) = [(HALF_WIDTH, 1.5), (1.5, -1.5), (-1.5, -HALF_WIDTH)][column];
+
+    let rectangle = match half {
+        CourtHalf::Near => (left, near, right - left, far - near),
+        CourtHalf::Far => (-right, -far, right - left, far - near),
+    };
+
+    Some(rectangle)
+}
+
+fn evaluation_colour(evaluation: Evaluation) -> &'static str {
+    match evaluation {
+        Evaluation::Hashtag => "#2ca02c",
+        Evaluation::Plus => "#98df8a",
+        Evaluation::Exclamation | Evaluation::Minus => "#7f7f7f",
+        Evaluation::Slash => "#ff7f0e",
+        Evaluation::Equal => "#d62728",
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+pub fn trajectory_chart(trajectories: &[Trajectory], width: u32) -> String {
+    let mut chart = CourtChart::new(width);
+    chart.add_trajectories(trajectories);
+    chart.to_svg()
+}
+
+pub fn heatmap_chart(heatmap: &Heatmap, width: u32) -> String {
+    let mut chart = CourtChart::new(width);
+    chart.add_heatmap(heatmap);
+    chart.to_svg()
+}
+
+// Landing zones of the serves on the far half, labelled with the number of serves and aces
+pub fn serve_chart(matrix: &ServeMatrix, width: u32) -> String {
+    let mut chart = CourtChart::new(width);
+
+    let counts = (1..=9)
+        .map(|zone| (zone, matrix.landing_count(zone)))
+        .collect::<Vec<_>>();
+    let max = counts.iter().map(|(_, c)| c.total()).max().unwrap_or(0);
+
+    for (zone, counts) in counts {
+        if counts.total() == 0 {
+            continue;
+        }
+
+        chart.add_zone(
+            CourtHalf::Far,
+            zone,
+            counts.total() as f64 / max as f64,
+            &format!("{} ({}#)", counts.total(), counts.hashtag),
+        );
+    }
+
+    chart.to_svg()
+}
+
+// Receptions on the near half by zone, labelled with the number of receptions and the share
+// graded `+` or `#`. Pass a shirt number to chart a single receiver.
+pub fn reception_chart(matrix: &ReceptionMatrix, player_number: Option<u8>, width: u32) -> String {
+    let mut chart = CourtChart::new(width);
+
+    let counts = (1..=9)
+        .map(|zone| {
+            let counts = match player_number {
+                Some(player_number) => matrix.count(player_number, zone),
+                None => matrix.landing_count(zone),
+            };
+
+            (zone, counts)
+        })
+        .collect::<Vec<_>>();
+    let max = counts.iter().map(|(_, c)| c.total()).max().unwrap_or(0);
+
+    for (zone, counts) in counts {
+        if counts.total() == 0 {
+            continue;
+        }
+
+        let positive = (counts.plus + counts.hashtag) * 100 / counts.total();
+
+        chart.add_zone(
+            CourtHalf::Near,
+            zone,
+            counts.total() as f64 / max as f64,
+            &format!("{} ({}%)", counts.total(), positive),
+        );
+    }
+
+    chart.to_svg()
+}
@@ -0,0 +1,298 @@
+use crate::data_formats::{
+    AutomaticCodeExplanation, CodeExplanation, ScoutFile, SetPoints, Skill, TeamSide,
+};
+use crate::rally::Rally;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Finding {
+    // A point code that does not add exactly one point to the team that won the rally
+    ScoreNotAdvancedByOne {
+        action_index: usize,
+        set: u8,
+        previous: SetPoints,
+        scored: SetPoints,
+    },
+    // The last point code of a set disagrees with the final score in [3SET]
+    SetScoreMismatch {
+        set: u8,
+        recorded: SetPoints,
+        scouted: SetPoints,
+    },
+    // `Team::sets_won` disagrees with the sets won in the scouted rallies
+    SetsWonMismatch {
+        team: TeamSide,
+        recorded: u8,
+        scouted: u8,
+    },
+    UnknownPlayer {
+        action_index: usize,
+        team: TeamSide,
+        player_number: u8,
+    },
+    SubstitutedPlayerNotOnCourt {
+        action_index: usize,
+        team: TeamSide,
+        player_number: u8,
+    },
+    SubstitutePlayerAlreadyOnCourt {
+        action_index: usize,
+        team: TeamSide,
+        player_number: u8,
+    },
+    // The setter position did not rotate after a side-out, or rotated without one
+    UnexpectedSetterPosition {
+        action_index: usize,
+        team: TeamSide,
+        expected: u8,
+        found: u8,
+    },
+    // A rally with touches whose first touch is not a serve
+    RallyWithoutServe {
+        action_index: usize,
+        set: u8,
+    },
+}
+
+pub fn validate(scout_file: &ScoutFile) -> Vec<Finding> {
+    let rallies = scout_file.rallies();
+
+    let mut findings = Vec::new();
+
+    validate_score_progression(&rallies, &mut findings);
+    validate_set_results(scout_file, &rallies, &mut findings);
+    validate_players(scout_file, &mut findings);
+    validate_rotations(&rallies, &mut findings);
+    validate_serves(&rallies, &mut findings);
+
+    findings
+}
+
+fn validate_score_progression(rallies: &[Rally], findings: &mut Vec<Finding>) {
+    for rally in rallies {
+        let point = match rally.point {
+            Some(point) => point,
+            None => continue,
+        };
+
+        let (home, visiting) = match point.team {
+            TeamSide::Home => (rally.home_score + 1, rally.visiting_score),
+            TeamSide::Visiting => (rally.home_score, rally.visiting_score + 1),
+        };
+
+        if (point.home_score, point.visiting_score) != (home, visiting) {
+            findings.push(Finding::ScoreNotAdvancedByOne {
+                action_index: rally.start_index + rally.actions.len() - 1,
+                set: rally.set,
+                previous: SetPoints::new(rally.home_score, rally.visiting_score),
+                scored: SetPoints::new(point.home_score, point.visiting_score),
+            });
+        }
+    }
+}
+
+// Final score of every set according to the point codes
+fn scouted_set_scores(rallies: &[Rally]) -> Vec<(u8, SetPoints)> {
+    let mut scores: Vec<(u8, SetPoints)> = Vec::new();
+
+    for rally in rallies {
+        let point = match rally.point {
+            Some(point) => point,
+            None => continue,
+        };
+
+        let score = SetPoints::new(point.home_score, point.visiting_score);
+
+        match scores.last_mut() {
+            Some((set, last)) if *set == rally.set => *last = score,
+            _ => scores.push((rally.set, score)),
+        }
+    }
+
+    scores
+}
+
+fn validate_set_results(scout_file: &ScoutFile, rallies: &[Rally], findings: &mut Vec<Finding>) {
+    let scouted = scouted_set_scores(rallies);
+
+    for set in &scout_file.sets {
+        let recorded = &set.fourth_quarter;
+        let scouted = scouted
+            .iter()
+            .find(|(set_number, _)| *set_number == set.set_number)
+            .map(|(_, score)| SetPoints::new(score.home, score.visiting))
+            .unwrap_or(SetPoints::new(0, 0));
+
+        if (recorded.home, recorded.visiting) != (scouted.home, scouted.visiting) {
+            findings.push(Finding::SetScoreMismatch {
+                set: set.set_number,
+                recorded: SetPoints::new(recorded.home, recorded.visiting),
+                scouted,
+            });
+        }
+    }
+
+    let home_sets = scouted
+        .iter()
+        .filter(|(_, score)| score.home > score.visiting)
+        .count() as u8;
+    let visiting_sets = scouted
+        .iter()
+        .filter(|(_, score)| score.visiting > score.home)
+        .count() as u8;
+
+    for (team, scouted) in [
+        (TeamSide::Home, home_sets),
+        (TeamSide::Visiting, visiting_sets),
+    ] {
+        let recorded = scout_file.team(team).sets_won;
+
+        if recorded != scouted {
+            findings.push(Finding::SetsWonMismatch {
+                team,
+                recorded,
+                scouted,
+            });
+        }
+    }
+}
+
+fn validate_players(scout_file: &ScoutFile, findings: &mut Vec<Finding>) {
+    for reference in scout_file.unknown_player_references() {
+        findings.push(Finding::UnknownPlayer {
+            action_index: reference.action_index,
+            team: reference.team,
+            player_number: reference.player_number,
+        });
+    }
+
+    let mut home_lineup: &[u8] = &[];
+    let mut visiting_lineup: &[u8] = &[];
+
+    for (action_index, action) in scout_file.actions.iter().enumerate() {
+        if let CodeExplanation::AutomaticCode(AutomaticCodeExplanation::Substitution(
+            substitution,
+        )) = &action.code_explanation
+        {
+            let lineup = match substitution.team {
+                TeamSide::Home => home_lineup,
+                TeamSide::Visiting => visiting_lineup,
+            };
+
+            // Without a lineup there is nothing to check against
+            if !lineup.is_empty() {
+                if !lineup.contains(&substitution.player_out) {
+                    findings.push(Finding::SubstitutedPlayerNotOnCourt {
+                        action_index,
+                        team: substitution.team,
+                        player_number: substitution.player_out,
+                    });
+                }
+
+                if lineup.contains(&substitution.player_in) {
+                    findings.push(Finding::SubstitutePlayerAlreadyOnCourt {
+                        action_index,
+                        team: substitution.team,
+                        player_number: substitution.player_in,
+                    });
+                }
+            }
+        }
+
+        if !action.home_lineup.is_empty() {
+            home_lineup = &action.home_lineup;
+        }
+
+        if !action.visiting_lineup.is_empty() {
+            visiting_lineup = &action.visiting_lineup;
+        }
+    }
+}
+
+// Players rotate clockwise on a side-out, which moves the setter from position 1 to 6, 6 to 5...
+fn rotate(setter_position: u8) -> u8 {
+    if setter_position <= 1 {
+        6
+    } else {
+        setter_position - 1
+    }
+}
+
+// Setter position of the team from a `*z`/`az` code of the rally itself. `Rally::setter_position`
+// carries the last code forward, which is stale when the scout left the code out of a rally.
+fn scouted_setter_position(rally: &Rally, team: TeamSide) -> Option<u8> {
+    rally
+        .actions
+        .iter()
+        .rev()
+        .find_map(|action| match &action.code_explanation {
+            CodeExplanation::AutomaticCode(AutomaticCodeExplanation::SetterPosition(position))
+                if position.team == team =>
+            {
+                Some(position.setter_position)
+            }
+            _ => None,
+        })
+}
+
+fn validate_rotations(rallies: &[Rally], findings: &mut Vec<Finding>) {
+    for pair in rallies.windows(2) {
+        let (previous, current) = (&pair[0], &pair[1]);
+
+        if previous.set != current.set {
+            continue;
+        }
+
+        let (serving, winner) = match (previous.serving_team(), previous.winner()) {
+            (Some(serving), Some(winner)) => (serving, winner),
+            _ => continue,
+        };
+
+        for team in [TeamSide::Home, TeamSide::Visiting] {
+            let (before, after) = match (
+                scouted_setter_position(previous, team),
+                scouted_setter_position(current, team),
+            ) {
+                (Some(before), Some(after)) => (before, after),
+                _ => continue,
+            };
+
+            let expected = if winner == team && serving != team {
+                rotate(before)
+            } else {
+                before
+            };
+
+            if after != expected {
+                findings.push(Finding::UnexpectedSetterPosition {
+                    action_index: current.start_index,
+                    team,
+                    expected,
+                    found: after,
+                });
+            }
+        }
+    }
+}
+
+fn validate_serves(rallies: &[Rally], findings: &mut Vec<Finding>) {
+    for rally in rallies {
+        let first_touch = rally.touch_actions().next();
+
+        if let Some((action, touch)) = first_touch {
+            if !matches!(touch.skill, Skill::Serve) {
+                let offset = rally
+                    .actions
+                    .iter()
+                    .position(|a| std::ptr::eq(a, action))
+                    .unwrap_or(0);
+
+                findings.push(Finding::RallyWithoutServe {
+                    action_index: rally.start_index + offset,
+                    set: rally.set,
+                });
+            }
+        }
+    }
+}
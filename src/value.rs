@@ -0,0 +1,221 @@
+use crate::data_formats::{CodeExplanation, Evaluation, Player, ScoutFile, Skill, TeamSide};
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OutcomeValue {
+    pub skill: Skill,
+    pub evaluation: Evaluation,
+    pub touches: u32,
+    // Touches after which the team of the touch won the rally
+    pub won: u32,
+}
+
+impl OutcomeValue {
+    // Between 0 and 1
+    pub fn win_probability(&self) -> Option<f64> {
+        match self.touches {
+            0 => None,
+            touches => Some(self.won as f64 / touches as f64),
+        }
+    }
+}
+
+// Empirical probability that the acting team wins the rally after each skill and evaluation,
+// e.g. a `+` reception against P(side-out). Rallies without a point code are left out.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ValueTable {
+    pub outcomes: Vec<OutcomeValue>,
+}
+
+impl ValueTable {
+    pub fn from_file(scout_file: &ScoutFile) -> ValueTable {
+        let mut table = ValueTable::default();
+        table.add_file(scout_file);
+        table
+    }
+
+    pub fn from_files(scout_files: &[ScoutFile]) -> ValueTable {
+        let mut table = ValueTable::default();
+
+        for scout_file in scout_files {
+            table.add_file(scout_file);
+        }
+
+        table
+    }
+
+    pub fn add_file(&mut self, scout_file: &ScoutFile) {
+        for rally in scout_file.rallies() {
+            let winner = match rally.winner() {
+                Some(winner) => winner,
+                None => continue,
+            };
+
+            for touch in rally.touches() {
+                let index = match self.outcomes.iter().position(|outcome| {
+                    outcome.skill == touch.skill && outcome.evaluation == touch.evaluation
+                }) {
+                    Some(index) => index,
+                    None => {
+                        self.outcomes.push(OutcomeValue {
+                            skill: touch.skill,
+                            evaluation: touch.evaluation,
+                            touches: 0,
+                            won: 0,
+                        });
+                        self.outcomes.len() - 1
+                    }
+                };
+
+                self.outcomes[index].touches += 1;
+
+                if touch.team == winner {
+                    self.outcomes[index].won += 1;
+                }
+            }
+        }
+    }
+
+    pub fn outcome(&self, skill: Skill, evaluation: Evaluation) -> Option<&OutcomeValue> {
+        self.outcomes
+            .iter()
+            .find(|outcome| outcome.skill == skill && outcome.evaluation == evaluation)
+    }
+
+    pub fn win_probability(&self, skill: Skill, evaluation: Evaluation) -> Option<f64> {
+        self.outcome(skill, evaluation)
+            .and_then(OutcomeValue::win_probability)
+    }
+
+    // Probability that the serving team wins the rally before the serve, i.e. over every serve
+    pub fn serve_win_probability(&self) -> Option<f64> {
+        let (touches, won) = self
+            .outcomes
+            .iter()
+            .filter(|outcome| outcome.skill == Skill::Serve)
+            .fold((0u32, 0u32), |(touches, won), outcome| {
+                (touches + outcome.touches, won + outcome.won)
+            });
+
+        match touches {
+            0 => None,
+            touches => Some(won as f64 / touches as f64),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ActionValue {
+    // Index into `ScoutFile::actions`
+    pub action_index: usize,
+    pub team: TeamSide,
+    pub player_number: u8,
+    pub skill: Skill,
+    pub evaluation: Evaluation,
+    // Win probability of the team of the touch before and after it
+    pub before: f64,
+    pub after: f64,
+}
+
+impl ActionValue {
+    pub fn value_added(&self) -> f64 {
+        self.after - self.before
+    }
+}
+
+// Value of every touch of the file against `table`. The value before a touch is the value after
+// the previous touch, from the point of view of the team of the touch, and the serve starts from
+// `ValueTable::serve_win_probability`. Touches with an outcome missing from the table are worth 0.
+pub fn action_values(scout_file: &ScoutFile, table: &ValueTable) -> Vec<ActionValue> {
+    let serve = table.serve_win_probability().unwrap_or(0.5);
+    let mut values = Vec::new();
+
+    for rally in scout_file.rallies() {
+        // Win probability of the home team after the last touch
+        let mut home: Option<f64> = None;
+
+        for (offset, action) in rally.actions.iter().enumerate() {
+            let touch = match &action.code_explanation {
+                CodeExplanation::ManualCode(touch) => touch,
+                _ => continue,
+            };
+
+            let before = match (home, touch.team) {
+                (Some(home), TeamSide::Home) => home,
+                (Some(home), TeamSide::Visiting) => 1.0 - home,
+                (None, _) => serve,
+            };
+            let after = table
+                .win_probability(touch.skill, touch.evaluation)
+                .unwrap_or(before);
+
+            home = Some(match touch.team {
+                TeamSide::Home => after,
+                TeamSide::Visiting => 1.0 - after,
+            });
+
+            values.push(ActionValue {
+                action_index: rally.start_index + offset,
+                team: touch.team,
+                player_number: touch.player_number,
+                skill: touch.skill,
+                evaluation: touch.evaluation,
+                before,
+                after,
+            });
+        }
+    }
+
+    values
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerValue {
+    pub team: TeamSide,
+    pub player_number: u8,
+    pub player: Option<Player>,
+    pub touches: u32,
+    // Sum of the value added of the player's touches
+    pub value_added: f64,
+}
+
+impl PlayerValue {
+    pub fn value_per_touch(&self) -> Option<f64> {
+        match self.touches {
+            0 => None,
+            touches => Some(self.value_added / touches as f64),
+        }
+    }
+}
+
+// Players of both teams rated by the value they added, highest first
+pub fn player_values(scout_file: &ScoutFile, table: &ValueTable) -> Vec<PlayerValue> {
+    let mut players: Vec<PlayerValue> = Vec::new();
+
+    for value in action_values(scout_file, table) {
+        let index = match players.iter().position(|player| {
+            player.team == value.team && player.player_number == value.player_number
+        }) {
+            Some(index) => index,
+            None => {
+                players.push(PlayerValue {
+                    team: value.team,
+                    player_number: value.player_number,
+                    player: scout_file.player(value.team, value.player_number).cloned(),
+                    touches: 0,
+                    value_added: 0.0,
+                });
+                players.len() - 1
+            }
+        };
+
+        players[index].touches += 1;
+        players[index].value_added += value.value_added();
+    }
+
+    players.sort_by(|a, b| b.value_added.total_cmp(&a.value_added));
+    players
+}
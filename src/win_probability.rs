@@ -0,0 +1,322 @@
+use crate::data_formats::{ScoutFile, TeamSide};
+
+// Probability of the home team winning a rally depending on who serves
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointModel {
+    // Home team wins the rally while serving
+    pub home_serve: f64,
+    // Home team wins the rally while the visiting team serves
+    pub visiting_serve: f64,
+}
+
+impl PointModel {
+    pub fn new(home_serve: f64, visiting_serve: f64) -> PointModel {
+        PointModel {
+            home_serve,
+            visiting_serve,
+        }
+    }
+
+    // The home team wins every rally with probability `probability`, whoever serves
+    pub fn constant(probability: f64) -> PointModel {
+        PointModel::new(probability, probability)
+    }
+
+    // Break point rates of both teams over the rallies of the file, 0.5 for a team that never
+    // served
+    pub fn from_file(scout_file: &ScoutFile) -> PointModel {
+        let mut served = [0u32, 0u32];
+        let mut won = [0u32, 0u32];
+
+        for rally in scout_file.rallies() {
+            if let (Some(serving), Some(winner)) = (rally.serving_team(), rally.winner()) {
+                let index = serving as usize;
+
+                served[index] += 1;
+
+                if winner == TeamSide::Home {
+                    won[index] += 1;
+                }
+            }
+        }
+
+        let rate = |index: usize| match served[index] {
+            0 => 0.5,
+            served => won[index] as f64 / served as f64,
+        };
+
+        PointModel::new(rate(0), rate(1))
+    }
+
+    fn home_point(&self, serving: TeamSide) -> f64 {
+        match serving {
+            TeamSide::Home => self.home_serve,
+            TeamSide::Visiting => self.visiting_serve,
+        }
+    }
+
+    // Home team wins a set from a tie at 24-24 (14-14 in the fifth set) or later. Solves the two
+    // equations of the tied states, the team that won the last point serves from the lead on.
+    fn deuce(&self, serving: TeamSide) -> f64 {
+        let home = self.home_serve;
+        let visiting = self.visiting_serve;
+
+        // tie(H) = home * lead(H) + (1 - home) * trail(V)
+        // tie(V) = visiting * lead(H) + (1 - visiting) * trail(V)
+        // lead(H) = home + (1 - home) * tie(V), trail(V) = visiting * tie(H)
+        let a11 = 1.0 - (1.0 - home) * visiting;
+        let a12 = -home * (1.0 - home);
+        let a21 = -(1.0 - visiting) * visiting;
+        let a22 = 1.0 - visiting * (1.0 - home);
+        let b1 = home * home;
+        let b2 = visiting * home;
+        let determinant = a11 * a22 - a12 * a21;
+
+        // Both teams always lose their serve, the set never ends
+        if determinant.abs() < 1e-12 {
+            return 0.5;
+        }
+
+        match serving {
+            TeamSide::Home => (b1 * a22 - a12 * b2) / determinant,
+            TeamSide::Visiting => (a11 * b2 - b1 * a21) / determinant,
+        }
+    }
+}
+
+// Points to win set `set_number` of a best of five match
+pub fn set_target(set_number: u8) -> u8 {
+    if set_number >= 5 {
+        15
+    } else {
+        25
+    }
+}
+
+// Probability of the home team winning the set from the score before a rally served by `serving`
+pub fn set_win_probability(
+    model: &PointModel,
+    home_score: u8,
+    visiting_score: u8,
+    serving: TeamSide,
+    set_number: u8,
+) -> f64 {
+    let target = set_target(set_number);
+
+    // Tied points past the target come off both scores, 27-26 plays like 25-24
+    let extra = home_score.min(visiting_score).saturating_sub(target - 1);
+    let home_score = home_score - extra;
+    let visiting_score = visiting_score - extra;
+
+    if home_score >= target && home_score >= visiting_score + 2 {
+        return 1.0;
+    }
+
+    if visiting_score >= target && visiting_score >= home_score + 2 {
+        return 0.0;
+    }
+
+    // Probabilities by score, per serving team
+    let size = target as usize + 2;
+    let mut table = vec![[None; 2]; size * size];
+
+    set_probability(
+        model,
+        target,
+        home_score,
+        visiting_score,
+        serving,
+        &mut table,
+    )
+}
+
+fn set_probability(
+    model: &PointModel,
+    target: u8,
+    home_score: u8,
+    visiting_score: u8,
+    serving: TeamSide,
+    table: &mut [[Option<f64>; 2]],
+) -> f64 {
+    if home_score >= target && home_score >= visiting_score + 2 {
+        return 1.0;
+    }
+
+    if visiting_score >= target && visiting_score >= home_score + 2 {
+        return 0.0;
+    }
+
+    if home_score == visiting_score && home_score >= target - 1 {
+        return model.deuce(serving);
+    }
+
+    let index = home_score as usize * (target as usize + 2) + visiting_score as usize;
+
+    if let Some(probability) = table[index][serving as usize] {
+        return probability;
+    }
+
+    let point = model.home_point(serving);
+    let probability = point
+        * set_probability(
+            model,
+            target,
+            home_score + 1,
+            visiting_score,
+            TeamSide::Home,
+            table,
+        )
+        + (1.0 - point)
+            * set_probability(
+                model,
+                target,
+                home_score,
+                visiting_score + 1,
+                TeamSide::Visiting,
+                table,
+            );
+
+    table[index][serving as usize] = Some(probability);
+    probability
+}
+
+// Home team wins set `set_number` from 0-0, averaged over both teams serving first since the
+// serve of the next set is not known yet
+fn fresh_set_probability(model: &PointModel, set_number: u8) -> f64 {
+    (set_win_probability(model, 0, 0, TeamSide::Home, set_number)
+        + set_win_probability(model, 0, 0, TeamSide::Visiting, set_number))
+        / 2.0
+}
+
+// Home team wins the best of five match after `home_sets` and `visiting_sets`, before set
+// `set_number` is started
+fn remaining_match_probability(
+    model: &PointModel,
+    home_sets: u8,
+    visiting_sets: u8,
+    set_number: u8,
+) -> f64 {
+    if home_sets >= 3 {
+        return 1.0;
+    }
+
+    if visiting_sets >= 3 {
+        return 0.0;
+    }
+
+    let set = fresh_set_probability(model, set_number);
+
+    set * remaining_match_probability(model, home_sets + 1, visiting_sets, set_number + 1)
+        + (1.0 - set)
+            * remaining_match_probability(model, home_sets, visiting_sets + 1, set_number + 1)
+}
+
+// Probability of the home team winning the best of five match from the sets already won and the
+// score of the current set before a rally served by `serving`
+pub fn match_win_probability(
+    model: &PointModel,
+    home_sets: u8,
+    visiting_sets: u8,
+    home_score: u8,
+    visiting_score: u8,
+    serving: TeamSide,
+) -> f64 {
+    if home_sets >= 3 {
+        return 1.0;
+    }
+
+    if visiting_sets >= 3 {
+        return 0.0;
+    }
+
+    let set_number = home_sets + visiting_sets + 1;
+    let set = set_win_probability(model, home_score, visiting_score, serving, set_number);
+
+    set * remaining_match_probability(model, home_sets + 1, visiting_sets, set_number + 1)
+        + (1.0 - set)
+            * remaining_match_probability(model, home_sets, visiting_sets + 1, set_number + 1)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RallyWinProbability {
+    // Index into `ScoutFile::actions` of the first row of the rally
+    pub action_index: usize,
+    pub set_number: u8,
+    // Sets won before the current set
+    pub home_sets: u8,
+    pub visiting_sets: u8,
+    // Score before the rally
+    pub home_score: u8,
+    pub visiting_score: u8,
+    pub serving_team: TeamSide,
+    // Probabilities of the home team winning, the visiting team's are 1 minus these
+    pub set_probability: f64,
+    pub match_probability: f64,
+}
+
+// Pre-rally set and match win probabilities for every rally with a point. Rallies without a serve
+// code are served by the winner of the previous point, the home team at the start of a set.
+pub fn win_probabilities(scout_file: &ScoutFile, model: &PointModel) -> Vec<RallyWinProbability> {
+    let mut probabilities = Vec::new();
+    let mut sets = [0u8, 0u8];
+    let mut set_number = None;
+    let mut last = (0, 0);
+    let mut last_winner = None;
+
+    for rally in scout_file.rallies() {
+        if set_number != Some(rally.set) {
+            if set_number.is_some() {
+                match last {
+                    (home, visiting) if home > visiting => sets[0] += 1,
+                    (home, visiting) if visiting > home => sets[1] += 1,
+                    _ => {}
+                }
+            }
+
+            set_number = Some(rally.set);
+            last_winner = None;
+        }
+
+        let point = match rally.point {
+            Some(point) => point,
+            None => continue,
+        };
+
+        let serving_team = rally
+            .serving_team()
+            .or(last_winner)
+            .unwrap_or(TeamSide::Home);
+
+        probabilities.push(RallyWinProbability {
+            action_index: rally.start_index,
+            set_number: rally.set,
+            home_sets: sets[0],
+            visiting_sets: sets[1],
+            home_score: rally.home_score,
+            visiting_score: rally.visiting_score,
+            serving_team,
+            set_probability: set_win_probability(
+                model,
+                rally.home_score,
+                rally.visiting_score,
+                serving_team,
+                rally.set,
+            ),
+            match_probability: match_win_probability(
+                model,
+                sets[0],
+                sets[1],
+                rally.home_score,
+                rally.visiting_score,
+                serving_team,
+            ),
+        });
+
+        last = (point.home_score, point.visiting_score);
+        last_winner = Some(point.team);
+    }
+
+    probabilities
+}
@@ -0,0 +1,213 @@
+use crate::data_formats::{ManualCodeExplanation, ScoutFile, Skill, TeamSide};
+use crate::statistics::EvaluationCounts;
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServeCell {
+    pub start_zone: Option<u8>,
+    pub end_zone: Option<u8>,
+    pub end_subzone: Option<char>,
+    // Evaluations of the serves, `#` is an ace and `=` an error
+    pub evaluations: EvaluationCounts,
+}
+
+// Serve zone x landing zone x outcome of one serving team
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ServeMatrix {
+    pub team: TeamSide,
+    pub cells: Vec<ServeCell>,
+}
+
+impl ServeMatrix {
+    fn add(&mut self, serve: &ManualCodeExplanation) {
+        let index = match self.cells.iter().position(|cell| {
+            cell.start_zone == serve.start_zone
+                && cell.end_zone == serve.end_zone
+                && cell.end_subzone == serve.end_subzone
+        }) {
+            Some(index) => index,
+            None => {
+                self.cells.push(ServeCell {
+                    start_zone: serve.start_zone,
+                    end_zone: serve.end_zone,
+                    end_subzone: serve.end_subzone,
+                    evaluations: EvaluationCounts::default(),
+                });
+                self.cells.len() - 1
+            }
+        };
+
+        self.cells[index].evaluations.add(serve.evaluation);
+    }
+
+    // Serves from `start_zone` landing in `end_zone`, over all subzones
+    pub fn count(&self, start_zone: u8, end_zone: u8) -> EvaluationCounts {
+        let mut counts = EvaluationCounts::default();
+
+        for cell in self
+            .cells
+            .iter()
+            .filter(|cell| cell.start_zone == Some(start_zone) && cell.end_zone == Some(end_zone))
+        {
+            counts.merge(&cell.evaluations);
+        }
+
+        counts
+    }
+
+    // Serves landing in `end_zone` from anywhere
+    pub fn landing_count(&self, end_zone: u8) -> EvaluationCounts {
+        let mut counts = EvaluationCounts::default();
+
+        for cell in self
+            .cells
+            .iter()
+            .filter(|cell| cell.end_zone == Some(end_zone))
+        {
+            counts.merge(&cell.evaluations);
+        }
+
+        counts
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReceptionCell {
+    pub player_number: u8,
+    pub end_zone: Option<u8>,
+    pub end_subzone: Option<char>,
+    pub evaluations: EvaluationCounts,
+}
+
+// Receiver x landing zone x reception grade of one receiving team
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReceptionMatrix {
+    pub team: TeamSide,
+    pub cells: Vec<ReceptionCell>,
+}
+
+impl ReceptionMatrix {
+    fn add(&mut self, reception: &ManualCodeExplanation) {
+        let index = match self.cells.iter().position(|cell| {
+            cell.player_number == reception.player_number
+                && cell.end_zone == reception.end_zone
+                && cell.end_subzone == reception.end_subzone
+        }) {
+            Some(index) => index,
+            None => {
+                self.cells.push(ReceptionCell {
+                    player_number: reception.player_number,
+                    end_zone: reception.end_zone,
+                    end_subzone: reception.end_subzone,
+                    evaluations: EvaluationCounts::default(),
+                });
+                self.cells.len() - 1
+            }
+        };
+
+        self.cells[index].evaluations.add(reception.evaluation);
+    }
+
+    pub fn receivers(&self) -> Vec<u8> {
+        let mut receivers = Vec::new();
+
+        for cell in &self.cells {
+            if !receivers.contains(&cell.player_number) {
+                receivers.push(cell.player_number);
+            }
+        }
+
+        receivers
+    }
+
+    // Receptions of one player in `end_zone`, over all subzones
+    pub fn count(&self, player_number: u8, end_zone: u8) -> EvaluationCounts {
+        let mut counts = EvaluationCounts::default();
+
+        for cell in self
+            .cells
+            .iter()
+            .filter(|cell| cell.player_number == player_number && cell.end_zone == Some(end_zone))
+        {
+            counts.merge(&cell.evaluations);
+        }
+
+        counts
+    }
+
+    // Receptions of every player in `end_zone`
+    pub fn landing_count(&self, end_zone: u8) -> EvaluationCounts {
+        let mut counts = EvaluationCounts::default();
+
+        for cell in self
+            .cells
+            .iter()
+            .filter(|cell| cell.end_zone == Some(end_zone))
+        {
+            counts.merge(&cell.evaluations);
+        }
+
+        counts
+    }
+
+    pub fn receiver_count(&self, player_number: u8) -> EvaluationCounts {
+        let mut counts = EvaluationCounts::default();
+
+        for cell in self
+            .cells
+            .iter()
+            .filter(|cell| cell.player_number == player_number)
+        {
+            counts.merge(&cell.evaluations);
+        }
+
+        counts
+    }
+}
+
+pub fn serve_matrices(scout_file: &ScoutFile) -> Vec<ServeMatrix> {
+    let mut matrices = TeamSide::ALL
+        .iter()
+        .map(|&team| ServeMatrix {
+            team,
+            cells: Vec::new(),
+        })
+        .collect::<Vec<ServeMatrix>>();
+
+    for serve in scout_file
+        .touches()
+        .filter(|touch| touch.skill == Skill::Serve)
+    {
+        match serve.team {
+            TeamSide::Home => matrices[0].add(serve),
+            TeamSide::Visiting => matrices[1].add(serve),
+        }
+    }
+
+    matrices
+}
+
+pub fn reception_matrices(scout_file: &ScoutFile) -> Vec<ReceptionMatrix> {
+    let mut matrices = TeamSide::ALL
+        .iter()
+        .map(|&team| ReceptionMatrix {
+            team,
+            cells: Vec::new(),
+        })
+        .collect::<Vec<ReceptionMatrix>>();
+
+    for reception in scout_file
+        .touches()
+        .filter(|touch| touch.skill == Skill::Reception)
+    {
+        match reception.team {
+            TeamSide::Home => matrices[0].add(reception),
+            TeamSide::Visiting => matrices[1].add(reception),
+        }
+    }
+
+    matrices
+}
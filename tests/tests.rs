@@ -1,6 +1,41 @@
 #[cfg(test)]
 mod tests {
+    use data_volley_reader::attacks::attack_statistics;
+    use data_volley_reader::chain::{chain_attack_statistics, possession_chains};
+    use data_volley_reader::collection::{MatchCollection, MatchFilter};
+    use data_volley_reader::court::{CourtCoordinate, CourtHalf};
+    use data_volley_reader::data_formats::{
+        ActionType, CodeExplanation, Evaluation, ParseCodeError, ScoutFile, ServeStyle, Skill,
+        SkillActionType, TeamSide, Tempo,
+    };
+    use data_volley_reader::datetime::{Date, DateOrder, Time};
+    use data_volley_reader::defense::{defense_statistics, DigSituation};
+    use data_volley_reader::heatmap::{heatmap, trajectories, TouchFilter, TrajectoryPoint};
+    use data_volley_reader::identity::{normalize_name, IdentityOverrides, IdentityResolver};
+    use data_volley_reader::momentum::timeline;
     use data_volley_reader::read;
+    use data_volley_reader::rotations::rotation_statistics;
+    use data_volley_reader::scouting::{scouting_report, AttackPhase};
+    use data_volley_reader::serves::serve_style_statistics;
+    use data_volley_reader::setter::{
+        setter_distribution, setter_distribution_for_files, SetDestination,
+    };
+    use data_volley_reader::simulation::{
+        next_rotation, predict, simulate_set, NextEvent, Random, TeamModel, TouchState,
+    };
+    use data_volley_reader::statistics::{player_statistics, team_statistics};
+    use data_volley_reader::validation::{validate, Finding};
+    use data_volley_reader::value::{action_values, player_values, ValueTable};
+    use data_volley_reader::win_probability::{
+        match_win_probability, set_win_probability, win_probabilities, PointModel,
+    };
+    use data_volley_reader::zones::{reception_matrices, serve_matrices};
+
+    fn read_test_file() -> ScoutFile {
+        let file = std::fs::File::open("tests/test.dvw").unwrap();
+        let mut buffer = std::io::BufReader::new(file);
+        read(&mut buffer).unwrap()
+    }
 
     #[test]
     fn test_file() {
@@ -9,4 +44,1220 @@ mod tests {
         let scout_file = read(&mut buffer).unwrap();
         dbg!(scout_file);
     }
+
+    #[test]
+    fn test_player_lookup() {
+        let scout_file = read_test_file();
+
+        // *19SM+~~~78A~~~00 is the first serve of the match
+        let serve = scout_file
+            .actions
+            .iter()
+            .find(|action| action.code.starts_with("*19SM"))
+            .unwrap();
+
+        let player = scout_file.player_for(serve).unwrap();
+        assert_eq!(player.last_name, "Shields");
+        assert_eq!(
+            scout_file.team_for(serve).unwrap().team_name,
+            "University of Louisville"
+        );
+
+        let roster = scout_file.roster(TeamSide::Visiting);
+        assert_eq!(roster.len(), scout_file.visiting_players.len());
+        assert_eq!(roster[&10].last_name, "Peterson");
+        assert_eq!(
+            scout_file.team(TeamSide::Visiting).team_name,
+            "University of Dayton"
+        );
+
+        assert!(scout_file.unknown_player_references().is_empty());
+    }
+
+    #[test]
+    fn test_validation() {
+        let scout_file = read_test_file();
+        assert_eq!(scout_file.rallies().len(), 125);
+        assert!(validate(&scout_file).is_empty());
+
+        // Skip a point and substitute a player who is not on court
+        let content = std::fs::read_to_string("tests/test.dvw")
+            .unwrap()
+            .replacen("*p02:00", "*p03:00", 1)
+            .replacen("ac15:18", "ac14:18", 1);
+        let scout_file = read(content.as_bytes()).unwrap();
+        let findings = validate(&scout_file);
+
+        assert!(findings
+            .iter()
+            .any(|finding| matches!(finding, Finding::ScoreNotAdvancedByOne { set: 1, .. })));
+        assert!(findings.iter().any(|finding| matches!(
+            finding,
+            Finding::SubstitutedPlayerNotOnCourt {
+                team: TeamSide::Visiting,
+                player_number: 14,
+                ..
+            }
+        )));
+
+        // A rally without its `*z` code, the setter position of the rally before is not compared
+        let line = "*z6;;;;;;;;1;6;5;1;652;;9;11;15;10;7;19;16;17;10;6;8;1;";
+        let content = std::fs::read_to_string("tests/test.dvw").unwrap();
+        let start = content.find(line).unwrap();
+        let end = start + content[start..].find('\n').unwrap() + 1;
+        let content = format!("{}{}", &content[..start], &content[end..]);
+        let scout_file = read(content.as_bytes()).unwrap();
+        assert_eq!(scout_file.actions.len(), read_test_file().actions.len() - 1);
+        assert!(validate(&scout_file).is_empty());
+
+        // A scouted setter position that does not follow the side-out is still reported
+        let content = std::fs::read_to_string("tests/test.dvw").unwrap().replacen(
+            line,
+            &line.replacen("*z6", "*z2", 1),
+            1,
+        );
+        let findings = validate(&read(content.as_bytes()).unwrap());
+        assert!(findings.iter().any(|finding| matches!(
+            finding,
+            Finding::UnexpectedSetterPosition {
+                team: TeamSide::Home,
+                expected: 6,
+                found: 2,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_dates() {
+        let scout_file = read_test_file();
+
+        // The generator date 09/19/2020 makes the file month first
+        assert_eq!(scout_file.game.date, Date::new(2019, 9, 1));
+        assert_eq!(scout_file.game.time, Time::new(5, 0, 0));
+
+        let creation = scout_file.metadata.creation_data.datetime.unwrap();
+        assert_eq!(creation.to_string(), "2020-09-19 08:38:36");
+
+        let serve = &scout_file.actions[4];
+        assert_eq!(serve.video_time, Some(std::time::Duration::from_secs(494)));
+
+        assert_eq!(
+            DateOrder::detect("LASTCHANGE-DAY: 19/09/2020"),
+            DateOrder::DayFirst
+        );
+        assert_eq!(
+            Date::parse("01.09.2019", DateOrder::DayFirst),
+            Date::new(2019, 9, 1)
+        );
+        assert_eq!(Date::parse("31/02/2019", DateOrder::DayFirst), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let scout_file = read_test_file();
+
+        let json = serde_json::to_value(&scout_file).unwrap();
+        assert_eq!(json["home_team"]["team_name"], "University of Louisville");
+        assert_eq!(
+            json["actions"][4]["code_explanation"]["manual_code"]["skill"],
+            "serve"
+        );
+
+        let deserialized: ScoutFile = serde_json::from_value(json).unwrap();
+        assert_eq!(deserialized, scout_file);
+    }
+
+    #[test]
+    fn test_code_conversions() {
+        assert_eq!(Skill::from_char('E'), Ok(Skill::Set));
+        assert_eq!(Skill::Set.to_char(), 'E');
+        assert_eq!("free ball".parse::<Skill>(), Ok(Skill::FreeBall));
+        assert_eq!("#".parse::<Evaluation>(), Ok(Evaluation::Hashtag));
+        assert_eq!("a".parse::<TeamSide>(), Ok(TeamSide::Visiting));
+        assert_eq!(
+            Skill::from_char('X'),
+            Err(ParseCodeError::InvalidSkill('X'))
+        );
+        assert_eq!(Skill::FreeBall.to_string(), "Free ball");
+
+        assert_eq!(
+            Skill::Attack.evaluation_description(Evaluation::Hashtag),
+            "Kill"
+        );
+        assert_eq!(
+            Skill::Reception.evaluation_description(Evaluation::Equal),
+            "Error"
+        );
+
+        assert_eq!(
+            CodeExplanation::new("*19XM+".to_string()),
+            CodeExplanation::InvalidCode
+        );
+
+        let scout_file = read_test_file();
+        for action in &scout_file.actions {
+            if let CodeExplanation::ManualCode(code) = &action.code_explanation {
+                assert!(action.code.starts_with(&code.main_code()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_ball_types() {
+        let action_type = |code: &str| match CodeExplanation::new(code.to_string()) {
+            CodeExplanation::ManualCode(code) => Some(code.action_type),
+            _ => None,
+        };
+
+        // U (super) as in the attack combinations of the test file
+        assert_eq!(action_type("*10AU#V6~47BH3"), Some(ActionType::Super));
+        assert_eq!(ActionType::Super.to_char(), 'U');
+        assert_eq!(action_type("a05AF-"), Some(ActionType::Fast));
+        assert_eq!(action_type("a05AN-"), Some(ActionType::Fast));
+
+        // Unknown ball types keep the touch
+        assert_eq!(action_type("*10AZ+"), Some(ActionType::Other));
+        assert_eq!(
+            ActionType::from_char('Z'),
+            Err(ParseCodeError::InvalidActionType('Z'))
+        );
+    }
+
+    #[test]
+    fn test_player_statistics() {
+        let scout_file = read_test_file();
+        let statistics = player_statistics(&scout_file);
+
+        let serves: u32 = statistics.iter().map(|player| player.skills.serves()).sum();
+        assert_eq!(serves, 125);
+
+        let peterson = statistics
+            .iter()
+            .find(|player| player.team == TeamSide::Visiting && player.player_number == 10)
+            .unwrap();
+        assert_eq!(peterson.player.as_ref().unwrap().last_name, "Peterson");
+        assert_eq!(peterson.skills.attacks(), 31);
+        assert_eq!(peterson.skills.kills(), 11);
+        assert_eq!(peterson.skills.attack_errors(), 4);
+        assert_eq!(peterson.skills.aces(), 3);
+        assert_eq!(peterson.skills.points(), 15);
+        assert_eq!(peterson.skills.reception_positive_percentage(), Some(50.0));
+    }
+
+    #[test]
+    fn test_team_statistics() {
+        let scout_file = read_test_file();
+        let reports = team_statistics(&scout_file);
+
+        let home = &reports[0];
+        assert_eq!(home.team, TeamSide::Home);
+        assert_eq!(home.match_statistics.points_won, 75);
+        assert_eq!(home.match_statistics.serve_points, 6);
+        assert_eq!(home.match_statistics.attack_points, 41);
+        assert_eq!(home.sets.len(), 3);
+        assert_eq!(home.sets[0].statistics.points_won, 25);
+        assert_eq!(home.sets[0].statistics.points_lost, 17);
+
+        let visiting = &reports[1];
+        assert_eq!(
+            visiting.match_statistics.side_out_attempts,
+            home.match_statistics.break_point_attempts
+        );
+        assert_eq!(
+            visiting.match_statistics.side_outs + home.match_statistics.break_points,
+            home.match_statistics.break_point_attempts
+        );
+    }
+
+    #[test]
+    fn test_rotation_statistics() {
+        let scout_file = read_test_file();
+        let reports = rotation_statistics(&scout_file);
+
+        for report in &reports {
+            assert_eq!(report.match_rotations.len(), 6);
+
+            let rallies: u32 = report
+                .match_rotations
+                .iter()
+                .map(|rotation| rotation.statistics.rallies)
+                .sum();
+            assert_eq!(rallies, 125);
+        }
+
+        let home_p1 = &reports[0].match_rotations[0].statistics;
+        assert_eq!((home_p1.side_outs, home_p1.side_out_attempts), (6, 8));
+        assert_eq!(
+            (home_p1.break_points, home_p1.break_point_attempts),
+            (14, 20)
+        );
+        assert_eq!(reports[0].sets.len(), 3);
+    }
+
+    #[test]
+    fn test_advanced_code() {
+        let scout_file = read_test_file();
+
+        assert_eq!(scout_file.attack_combinations.len(), 33);
+        assert_eq!(
+            scout_file.attack_combination("X5").unwrap().description,
+            "Go"
+        );
+        assert_eq!(
+            scout_file.setter_call("K1").unwrap().description,
+            "Quick ahead"
+        );
+        assert_eq!(
+            scout_file.setter_call("K1").unwrap().quick_run,
+            vec![
+                CourtCoordinate::new(49, 39).unwrap(),
+                CourtCoordinate::new(49, 45).unwrap(),
+                CourtCoordinate::new(49, 49).unwrap(),
+            ]
+        );
+
+        // a10AT-X5~46CH2~00F
+        let attack = scout_file
+            .touches()
+            .find(|touch| touch.skill == Skill::Attack)
+            .unwrap();
+        assert_eq!(attack.attack_combination(), Some("X5"));
+        assert_eq!(attack.start_zone, Some(4));
+        assert_eq!(attack.end_zone, Some(6));
+        assert_eq!(attack.end_subzone, Some('C'));
+        assert_eq!(attack.skill_type, Some('H'));
+        assert_eq!(attack.blockers(), Some(2));
+        assert_eq!(attack.custom, "00F");
+
+        let reports = attack_statistics(&scout_file);
+        let home = &reports[0];
+        assert_eq!(home.total.attempts(), 93);
+        assert_eq!(home.total.kills(), 41);
+
+        let x5 = home
+            .by_combination
+            .iter()
+            .find(|group| group.key.as_deref() == Some("X5"))
+            .unwrap();
+        assert_eq!(x5.statistics.attempts(), 13);
+
+        let attempts: u32 = home
+            .by_reception
+            .iter()
+            .map(|group| group.statistics.attempts())
+            .sum();
+        assert_eq!(attempts, 93);
+    }
+
+    #[test]
+    fn test_setter_distribution() {
+        let scout_file = read_test_file();
+        let distributions = setter_distribution(&scout_file);
+
+        let sets: u32 = distributions
+            .iter()
+            .map(|distribution| distribution.overall.sets())
+            .sum();
+        assert_eq!(sets, 190);
+
+        let shields = distributions
+            .iter()
+            .find(|distribution| distribution.team_id == "17" && distribution.setter_number == 19)
+            .unwrap();
+        assert_eq!(shields.overall.sets(), 45);
+        assert_eq!(
+            shields
+                .overall
+                .percentage(&SetDestination::Combination("X5".to_string())),
+            Some(8.0 * 100.0 / 45.0)
+        );
+
+        let rotation_sets: u32 = shields
+            .by_rotation
+            .iter()
+            .map(|split| split.distribution.sets())
+            .sum();
+        assert_eq!(rotation_sets, 45);
+
+        let combined = setter_distribution_for_files(&[scout_file.clone(), scout_file]);
+        assert_eq!(combined.len(), distributions.len());
+        assert_eq!(
+            combined[0].overall.sets(),
+            distributions[0].overall.sets() * 2
+        );
+    }
+
+    #[test]
+    fn test_zone_matrices() {
+        let scout_file = read_test_file();
+
+        let serves = serve_matrices(&scout_file);
+        let total: u32 = serves
+            .iter()
+            .flat_map(|matrix| &matrix.cells)
+            .map(|cell| cell.evaluations.total())
+            .sum();
+        assert_eq!(total, 125);
+
+        let home = &serves[0];
+        assert_eq!(home.count(7, 1).hashtag, 3);
+        assert_eq!(home.count(7, 1).total(), 8);
+
+        let receptions = reception_matrices(&scout_file);
+        let visiting = &receptions[1];
+        assert_eq!(visiting.receivers().len(), 9);
+        assert_eq!(visiting.receiver_count(20).total(), 21);
+        assert_eq!(visiting.count(20, 1).total(), 5);
+    }
+
+    #[test]
+    fn test_court_coordinates() {
+        let scout_file = read_test_file();
+
+        // *19SM+~~~78A~~~00;;;;0431;-1-1;7642
+        let serve = scout_file
+            .actions
+            .iter()
+            .find(|action| action.code.starts_with("*19SM"))
+            .unwrap();
+
+        let start = serve.start_coordinate.unwrap();
+        assert_eq!(start, CourtCoordinate { x: 31, y: 4 });
+        assert_eq!(start.half(), CourtHalf::Near);
+        assert!((start.y_metres() + 10.35).abs() < 1e-9);
+        assert!((start.x_metres() + 2.28).abs() < 1e-9);
+        assert_eq!(serve.mid_coordinate, None);
+
+        let end = serve.end_coordinate.unwrap();
+        assert_eq!(end.to_string(), "7642");
+        assert_eq!(end.half(), CourtHalf::Far);
+        assert_eq!((end.zone(), end.subzone()), (8, 'A'));
+
+        assert_eq!(CourtCoordinate::parse("-1-1"), None);
+        assert_eq!(CourtCoordinate::parse(""), None);
+
+        // The zones of every scouted serve and attack agree with their end coordinate
+        for (action, touch) in
+            scout_file
+                .actions
+                .iter()
+                .filter_map(|action| match &action.code_explanation {
+                    CodeExplanation::ManualCode(touch) => Some((action, touch)),
+                    _ => None,
+                })
+        {
+            if !matches!(touch.skill, Skill::Serve | Skill::Attack) {
+                continue;
+            }
+
+            if let (Some(end), Some(zone)) = (action.end_coordinate, touch.end_zone) {
+                assert_eq!(end.zone(), zone, "{}", action.code);
+            }
+
+            if let (Some(end), Some(subzone)) = (action.end_coordinate, touch.end_subzone) {
+                assert_eq!(end.subzone(), subzone, "{}", action.code);
+            }
+        }
+
+        let combination = scout_file.attack_combination("V5").unwrap();
+        assert_eq!(combination.coordinate.map(|c| c.zone()), Some(4));
+
+        // The 3 m and 6 m lines of the far half
+        let cell = |x, y| CourtCoordinate::new(x, y).unwrap();
+        assert!(cell(50, 63).distance_from_net() < 3.0);
+        assert!(cell(50, 64).distance_from_net() > 3.0);
+        assert_eq!((cell(50, 63).zone(), cell(50, 65).zone()), (3, 8));
+        assert_eq!((cell(50, 76).zone(), cell(50, 77).zone()), (8, 6));
+
+        // Go and X are set to the antennas, on the sidelines
+        for code in ["X5", "X6"] {
+            let coordinate = scout_file.attack_combination(code).unwrap().coordinate;
+            assert!((coordinate.unwrap().x_metres().abs() - 4.5).abs() < 0.1);
+        }
+
+        // Every serve starts behind the end line and between the sidelines
+        for action in &scout_file.actions {
+            if let (CodeExplanation::ManualCode(touch), Some(start)) =
+                (&action.code_explanation, action.start_coordinate)
+            {
+                if touch.skill == Skill::Serve {
+                    assert!(start.distance_from_net() > 9.0, "{}", action.code);
+                    assert!(start.x_metres().abs() < 4.5, "{}", action.code);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_heatmap() {
+        let scout_file = read_test_file();
+
+        let filter = TouchFilter {
+            team: Some(TeamSide::Home),
+            skill: Some(Skill::Attack),
+            ..Default::default()
+        };
+        let attacks = trajectories(&scout_file, &filter);
+        assert_eq!(attacks.len(), 93);
+        assert!(attacks.iter().all(|attack| attack.skill == Skill::Attack));
+
+        let normalized = attacks
+            .iter()
+            .map(|attack| attack.normalized())
+            .collect::<Vec<_>>();
+        assert!(normalized
+            .iter()
+            .all(|attack| attack.start.unwrap().half() == CourtHalf::Near));
+
+        let starts = heatmap(&normalized, TrajectoryPoint::Start, 4, 10);
+        assert_eq!(starts.total, 93);
+        let front_row: u32 = (0..4).map(|column| starts.get(column, 4)).sum();
+        assert_eq!(front_row, 88);
+
+        let ends = heatmap(&normalized, TrajectoryPoint::End, 4, 10);
+        let far_half: u32 = (0..4)
+            .flat_map(|column| (5..10).map(move |row| (column, row)))
+            .map(|(column, row)| ends.get(column, row))
+            .sum();
+        assert_eq!(far_half, 93);
+        assert_eq!(ends.density(1, 7), Some(17.0 / 93.0));
+
+        let kills = trajectories(
+            &scout_file,
+            &TouchFilter {
+                evaluation: Some(Evaluation::Hashtag),
+                ..filter
+            },
+        );
+        assert_eq!(kills.len(), 41);
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn test_svg() {
+        use data_volley_reader::heatmap::heatmap;
+        use data_volley_reader::svg::{
+            heatmap_chart, reception_chart, serve_chart, trajectory_chart,
+        };
+
+        let scout_file = read_test_file();
+
+        let serves = trajectories(
+            &scout_file,
+            &TouchFilter {
+                skill: Some(Skill::Serve),
+                ..Default::default()
+            },
+        )
+        .iter()
+        .map(|serve| serve.normalized())
+        .collect::<Vec<_>>();
+
+        let chart = trajectory_chart(&serves, 400);
+        assert!(chart.starts_with("<svg "));
+        assert!(chart.ends_with("</svg>"));
+        assert!(chart.contains(r#"width="400" height="750""#));
+        assert_eq!(chart.matches("<circle").count(), serves.len());
+
+        let chart = heatmap_chart(&heatmap(&serves, TrajectoryPoint::End, 10, 10), 400);
+        assert!(chart.contains("fill-opacity=\"0.80\""));
+
+        let chart = serve_chart(&serve_matrices(&scout_file)[0], 400);
+        assert!(chart.contains("(3#)"));
+
+        let receptions = &reception_matrices(&scout_file)[1];
+        let chart = reception_chart(receptions, Some(20), 400);
+        assert_eq!(chart.matches("<text").count(), 5);
+    }
+
+    #[test]
+    fn test_timeline() {
+        let scout_file = read_test_file();
+        let timeline = timeline(&scout_file);
+
+        assert_eq!(timeline.sets.len(), 3);
+        let points: usize = timeline.sets.iter().map(|set| set.points.len()).sum();
+        assert_eq!(points, 125);
+
+        let third = &timeline.sets[2];
+        let last = third.points.last().unwrap();
+        assert_eq!((last.home_score, last.visiting_score), (25, 19));
+        assert_eq!(last.difference(), 6);
+
+        let run = timeline.longest_run(TeamSide::Home).unwrap();
+        assert_eq!(run.length, 10);
+        assert_eq!((run.home_score, run.visiting_score), (14, 18));
+        assert_eq!(run.server, Some(17));
+        assert_eq!(third.longest_run(TeamSide::Visiting).unwrap().length, 5);
+
+        let runs: u32 = third.runs.iter().map(|run| run.length as u32).sum();
+        assert_eq!(runs, 44);
+        assert_eq!(third.runs_of(4).count(), 2);
+
+        assert!(timeline.sets.iter().all(|set| set.timeouts.is_empty()));
+
+        // A visiting timeout at 3-0 in the first set
+        let mut bytes = std::fs::read("tests/test.dvw").unwrap();
+        let line = b"*p03:00;;;;1187;-1-1;;;1;1;6;1;551;;19;9;11;15;10;7;1;16;17;10;6;8;";
+        let end = bytes
+            .windows(line.len())
+            .position(|window| window == line)
+            .unwrap()
+            + line.len();
+        bytes.splice(end..end, b"\naT;;;;;;;;1;1;6;1;560;;".iter().copied());
+
+        let scout_file = read(std::io::Cursor::new(bytes)).unwrap();
+        let timeline = data_volley_reader::momentum::timeline(&scout_file);
+        let timeout = &timeline.sets[0].timeouts[0];
+        assert_eq!(timeout.team, TeamSide::Visiting);
+        assert_eq!(timeout.points_played, 3);
+        assert_eq!((timeout.home_score, timeout.visiting_score), (3, 0));
+        assert_eq!(timeline.sets[0].points.len(), 42);
+        assert_eq!(
+            CodeExplanation::new("aT".to_string()).team(),
+            Some(TeamSide::Visiting)
+        );
+
+        // A home timeout after the last point of the first set, no touch follows it
+        let content = std::fs::read_to_string("tests/test.dvw").unwrap().replacen(
+            "**1set;",
+            "*T;;;;;;;;1;1;6;1;1730;;\n**1set;",
+            1,
+        );
+        let scout_file = read(content.as_bytes()).unwrap();
+        let timeline = data_volley_reader::momentum::timeline(&scout_file);
+        let first = &timeline.sets[0];
+        let last = first.points.last().unwrap();
+        assert_eq!(first.timeouts.len(), 1);
+        assert_eq!(first.timeouts[0].team, TeamSide::Home);
+        assert_eq!(first.timeouts[0].points_played, first.points.len());
+        assert_eq!(
+            (
+                first.timeouts[0].home_score,
+                first.timeouts[0].visiting_score
+            ),
+            (last.home_score, last.visiting_score)
+        );
+        assert!(timeline.sets[1].timeouts.is_empty());
+    }
+
+    #[test]
+    fn test_match_collection() {
+        let scout_file = read_test_file();
+        assert_eq!(scout_file.game.competition, "");
+        assert_eq!(scout_file.side_of("42"), Some(TeamSide::Visiting));
+
+        // The same match a week later with Shields wearing 91 instead of 19
+        let mut rematch = scout_file.clone();
+        rematch.game.date = Date::new(2019, 9, 8);
+        rematch.game.competition = "ACC".to_string();
+
+        for player in &mut rematch.home_players {
+            if player.player_number == 19 {
+                player.player_number = 91;
+            }
+        }
+
+        for action in &mut rematch.actions {
+            if let CodeExplanation::ManualCode(touch) = &mut action.code_explanation {
+                if touch.team == TeamSide::Home && touch.player_number == 19 {
+                    touch.player_number = 91;
+                }
+            }
+        }
+
+        let collection = MatchCollection::new(vec![scout_file, rematch]);
+        let everything = MatchFilter::default();
+
+        let report = collection.team_report("17", &everything);
+        assert_eq!(report.matches.len(), 2);
+        assert_eq!(report.total.points_won, 75 * 2);
+        assert_eq!(report.total.skills.kills(), 41 * 2);
+        assert_eq!(report.matches[1].opponent.team_name, "University of Dayton");
+
+        let players = collection.player_reports("17", &everything);
+        let shields = players
+            .iter()
+            .find(|report| report.key == "-296094")
+            .unwrap();
+        assert_eq!(shields.player_numbers, vec![19, 91]);
+        assert_eq!(shields.matches.len(), 2);
+        assert_eq!(
+            shields.total.serves(),
+            shields.matches[0].skills.serves() * 2
+        );
+
+        let acc = MatchFilter {
+            competition: Some("acc".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(collection.team_report("17", &acc).matches.len(), 1);
+
+        let september = MatchFilter {
+            from: Date::new(2019, 9, 2),
+            to: Date::new(2019, 9, 30),
+            opponent: Some("University of Dayton".to_string()),
+            ..Default::default()
+        };
+        let report = collection.team_report("17", &september);
+        assert_eq!(report.matches.len(), 1);
+        assert_eq!(report.matches[0].match_index, 1);
+
+        let opponent = MatchFilter {
+            opponent: Some("17".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(collection.team_report("42", &opponent).matches.len(), 2);
+        assert!(collection.team_report("99", &everything).matches.is_empty());
+    }
+
+    #[test]
+    fn test_identity_resolver() {
+        assert_eq!(normalize_name("Shields,  Shannon"), "shannon shields");
+        assert_eq!(normalize_name("Zoë  O'Neil"), "neil o zoe");
+        assert_eq!(normalize_name("ŠIMIĆ Ante"), normalize_name("ante šimić"));
+        assert_eq!(normalize_name("ŁUKASZ Żurek"), "lukasz zurek");
+
+        let volleymetrics = read_test_file();
+
+        // The same match exported by Data Volley, with its own ids and names written differently
+        let mut data_volley = volleymetrics.clone();
+        for (index, player) in data_volley.home_players.iter_mut().enumerate() {
+            player.player_id = format!("DV{}", index);
+            player.last_name = player.last_name.to_uppercase();
+        }
+
+        // Roe married and changed her name
+        let roe = data_volley
+            .home_players
+            .iter_mut()
+            .find(|player| player.last_name == "ROE")
+            .unwrap();
+        roe.last_name = "Smith".to_string();
+        let roe_id = roe.player_id.clone();
+
+        let mut overrides = IdentityOverrides::default();
+        overrides.add_player_id(&roe_id, "-11720");
+        let mut resolver = IdentityResolver::new(overrides);
+
+        let first = resolver.resolve_file(&volleymetrics);
+        let second = resolver.resolve_file(&data_volley);
+        assert_eq!(first.len(), second.len());
+        assert!(first.iter().all(|(player, key)| second[player] == *key));
+        assert_eq!(
+            resolver.identities.len(),
+            volleymetrics.home_players.len() + volleymetrics.visiting_players.len()
+        );
+
+        let shields = resolver.identity("-296094").unwrap();
+        assert_eq!(shields.player.last_name, "Shields");
+        assert_eq!(shields.team_ids, vec!["17"]);
+        assert_eq!(shields.player_ids.len(), 2);
+        assert_eq!(resolver.identity("-11720").unwrap().names.len(), 2);
+
+        // The home roster has two entries named Jamie Vasilou with different ids
+        let vasilou = resolver
+            .identities
+            .iter()
+            .filter(|identity| identity.names.contains(&"jamie vasilou".to_string()))
+            .count();
+        assert_eq!(vasilou, 2);
+
+        // Without the override Roe gets a second identity
+        let mut resolver = IdentityResolver::default();
+        resolver.resolve_file(&volleymetrics);
+        resolver.resolve_file(&data_volley);
+        assert_eq!(
+            resolver.identities.len(),
+            volleymetrics.home_players.len() + volleymetrics.visiting_players.len() + 1
+        );
+
+        // Ids never link two entries of one file
+        let mut shared_ids = volleymetrics.clone();
+        for player in shared_ids
+            .home_players
+            .iter_mut()
+            .chain(shared_ids.visiting_players.iter_mut())
+        {
+            player.player_id = "1".to_string();
+        }
+        let mut resolver = IdentityResolver::default();
+        let keys = resolver.resolve_file(&shared_ids);
+        assert_eq!(
+            resolver.identities.len(),
+            shared_ids.home_players.len() + shared_ids.visiting_players.len()
+        );
+        assert_eq!(
+            keys.values()
+                .collect::<std::collections::HashSet<_>>()
+                .len(),
+            keys.len()
+        );
+
+        // The home roster moved to another club, ids still link the players
+        let mut transferred = volleymetrics.clone();
+        transferred.home_team.team_id = "99".to_string();
+        let mut resolver = IdentityResolver::default();
+        let first = resolver.resolve_file(&volleymetrics);
+        let second = resolver.resolve_file(&transferred);
+        assert_eq!(first, second);
+        assert_eq!(
+            resolver.identity("-296094").unwrap().team_ids,
+            vec!["17", "99"]
+        );
+        assert_eq!(
+            resolver.identities.len(),
+            volleymetrics.home_players.len() + volleymetrics.visiting_players.len()
+        );
+
+        let collection = MatchCollection::new(vec![volleymetrics, data_volley]);
+        let players = collection.player_reports("17", &MatchFilter::default());
+        let shields = players
+            .iter()
+            .find(|report| report.key == "-296094")
+            .unwrap();
+        assert_eq!(shields.matches.len(), 2);
+    }
+
+    #[test]
+    fn test_scouting_report() {
+        let scout_file = read_test_file();
+        let scout_files = vec![scout_file.clone(), scout_file];
+
+        let report = scouting_report(&scout_files, "university of dayton");
+        assert_eq!(report.team_id, "42");
+        assert_eq!(report.matches, 2);
+
+        // Both copies of the 51 visiting serves
+        let serves: u32 = report
+            .serve_targets
+            .iter()
+            .map(|cell| cell.evaluations.total())
+            .sum();
+        assert_eq!(serves, 102);
+        assert_eq!(report.serve_targets[0].start_zone, Some(1));
+        assert_eq!(report.serve_targets[0].end_zone, Some(6));
+
+        let side_out = &report.attackers[0];
+        assert_eq!(side_out.phase, AttackPhase::SideOut);
+        assert_eq!(side_out.attackers[0].player_number, 10);
+        assert_eq!(side_out.attackers[0].statistics.kills(), 10);
+
+        let rotation_six = &report.combinations_by_rotation[5];
+        assert_eq!(rotation_six.rotation, 6);
+        assert_eq!(rotation_six.combinations[0].key.as_deref(), Some("X5"));
+
+        let setter = &report.setters[0];
+        assert_eq!(setter.setter_number, 8);
+        assert!(setter.under_pressure.sets() <= setter.overall.sets());
+
+        let receiver = report
+            .receivers
+            .iter()
+            .find(|receiver| receiver.player_number == 2)
+            .unwrap();
+        assert_eq!(receiver.evaluations.total(), 26);
+        assert_eq!(receiver.weakest_zone, Some(7));
+
+        // Players are merged by identity, not by shirt number
+        assert!(side_out.attackers[0].key.starts_with('-'));
+        let single = scouting_report(&scout_files[..1], "42");
+        assert_eq!(
+            single.attackers[0].attackers.len(),
+            side_out.attackers.len()
+        );
+
+        // In the second match the two leading side-out attackers swapped shirts
+        let ten = &single.attackers[0].attackers[0];
+        let other = &single.attackers[0].attackers[1];
+        let mut renumbered = scout_files[1].clone();
+        for player in &mut renumbered.visiting_players {
+            if player.player_number == ten.player_number {
+                player.player_number = other.player_number;
+            } else if player.player_number == other.player_number {
+                player.player_number = ten.player_number;
+            }
+        }
+        let swapped = scouting_report(&[scout_files[0].clone(), renumbered], "42");
+        let swapped_ten = swapped.attackers[0]
+            .attackers
+            .iter()
+            .find(|attacker| attacker.key == ten.key)
+            .unwrap();
+        assert_eq!(swapped_ten.player_number, other.player_number);
+        assert_eq!(
+            swapped_ten.statistics.attempts(),
+            ten.statistics.attempts() + other.statistics.attempts()
+        );
+
+        let home = scouting_report(&scout_files[..1], "17");
+        assert_eq!(home.team_name, "University of Louisville");
+        assert_eq!(scouting_report(&scout_files, "unknown").matches, 0);
+    }
+
+    #[test]
+    fn test_value_table() {
+        let scout_file = read_test_file();
+        let table = ValueTable::from_file(&scout_file);
+
+        // The serving team won 8 of the rallies after a `+` serve
+        let outcome = table.outcome(Skill::Serve, Evaluation::Plus).unwrap();
+        assert_eq!((outcome.touches, outcome.won), (24, 8));
+        assert_eq!(
+            table.win_probability(Skill::Attack, Evaluation::Hashtag),
+            Some(1.0)
+        );
+        assert_eq!(
+            table.win_probability(Skill::Reception, Evaluation::Equal),
+            Some(0.0)
+        );
+        assert_eq!(table.serve_win_probability(), Some(0.48));
+
+        let doubled = ValueTable::from_files(&[scout_file.clone(), scout_file.clone()]);
+        assert_eq!(
+            doubled
+                .outcome(Skill::Serve, Evaluation::Plus)
+                .unwrap()
+                .touches,
+            48
+        );
+        assert_eq!(
+            doubled.win_probability(Skill::Serve, Evaluation::Plus),
+            table.win_probability(Skill::Serve, Evaluation::Plus)
+        );
+
+        let values = action_values(&scout_file, &table);
+        assert_eq!(values[0].action_index, 4);
+        assert_eq!(values[0].skill, Skill::Serve);
+        assert_eq!(values[0].before, 0.48);
+        assert!(values[0].value_added() < 0.0);
+
+        // The reception starts from the serving team's chances turned around
+        assert_eq!(values[1].skill, Skill::Reception);
+        assert!((values[1].before - (1.0 - values[0].after)).abs() < 1e-9);
+
+        let players = player_values(&scout_file, &table);
+        assert_eq!(
+            (players[0].team, players[0].player_number),
+            (TeamSide::Home, 9)
+        );
+        assert!(players
+            .windows(2)
+            .all(|pair| pair[0].value_added >= pair[1].value_added));
+    }
+
+    #[test]
+    fn test_simulation() {
+        let scout_files = vec![read_test_file()];
+        let home = TeamModel::from_files(&scout_files, "17");
+        let visiting = TeamModel::from_files(&scout_files, "university of dayton");
+        assert_eq!(home.team_id, "17");
+        assert_eq!(visiting.team_id, "42");
+
+        // The 74 home serves start the home rallies
+        let serves = home.transition(None, None).unwrap();
+        assert_eq!(serves.total(), 74);
+        let ace = TouchState {
+            skill: Skill::Serve,
+            evaluation: Evaluation::Hashtag,
+        };
+        assert!(serves.probability(NextEvent::Touch(ace)).unwrap() > 0.0);
+
+        // A kill is followed by the point or by the opponent's block or dig error
+        let kill = TouchState {
+            skill: Skill::Attack,
+            evaluation: Evaluation::Hashtag,
+        };
+        let after_kill = home.transition(None, Some(kill)).unwrap();
+        assert_eq!(after_kill.total(), 41);
+        assert_eq!(after_kill.probability(NextEvent::Lost), Some(0.0));
+
+        // Rotations with too few touches fall back to the counts over every rotation
+        let rotation = home.transition(Some(1), None).unwrap();
+        assert!(rotation.rotation.is_none() || rotation.total() >= 10);
+
+        assert_eq!(next_rotation(1), 6);
+        assert_eq!(next_rotation(4), 3);
+
+        let mut random = Random::new(3);
+        let (points, opponent_points) = simulate_set(&home, &visiting, true, 5, &mut random);
+        assert!(points.max(opponent_points) >= 15);
+        assert!(points.abs_diff(opponent_points) >= 2);
+
+        let prediction = predict(&home, &visiting, 200, 7);
+        assert_eq!(prediction, predict(&home, &visiting, 200, 7));
+        assert!(prediction.match_win_probability > 0.5);
+        assert!(prediction.set_win_probability > 0.5);
+
+        let total: f64 = prediction
+            .results
+            .iter()
+            .map(|result| result.probability)
+            .sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(
+            (
+                prediction.results[0].sets_won,
+                prediction.results[0].sets_lost
+            ),
+            (3, 0)
+        );
+    }
+
+    #[test]
+    fn test_win_probability() {
+        let scout_file = read_test_file();
+
+        // Two points in a row win from a tie, p² / (p² + (1 - p)²)
+        let constant = PointModel::constant(0.6);
+        let deuce = 0.36 / 0.52;
+        assert!((set_win_probability(&constant, 24, 24, TeamSide::Home, 1) - deuce).abs() < 1e-9);
+        assert!((set_win_probability(&constant, 31, 31, TeamSide::Home, 1) - deuce).abs() < 1e-9);
+        assert!((set_win_probability(&constant, 14, 14, TeamSide::Home, 5) - deuce).abs() < 1e-9);
+        assert_eq!(
+            set_win_probability(&constant, 25, 23, TeamSide::Home, 1),
+            1.0
+        );
+        assert_eq!(
+            set_win_probability(&constant, 13, 15, TeamSide::Home, 5),
+            0.0
+        );
+
+        let even = PointModel::constant(0.5);
+        assert!((set_win_probability(&even, 0, 0, TeamSide::Home, 1) - 0.5).abs() < 1e-9);
+        assert!((match_win_probability(&even, 1, 1, 10, 10, TeamSide::Home) - 0.5).abs() < 1e-9);
+        assert_eq!(
+            match_win_probability(&even, 3, 1, 0, 0, TeamSide::Home),
+            1.0
+        );
+
+        let model = PointModel::from_file(&scout_file);
+        assert!((model.home_serve - 42.0 / 74.0).abs() < 1e-9);
+        assert!((model.visiting_serve - 33.0 / 51.0).abs() < 1e-9);
+
+        let probabilities = win_probabilities(&scout_file, &model);
+        assert_eq!(probabilities.len(), 125);
+        assert_eq!(probabilities[0].serving_team, TeamSide::Home);
+        assert!(probabilities[0].set_probability > 0.5);
+
+        let last = probabilities.last().unwrap();
+        assert_eq!(
+            (last.set_number, last.home_sets, last.visiting_sets),
+            (3, 2, 0)
+        );
+        assert_eq!((last.home_score, last.visiting_score), (24, 19));
+        assert!(last.match_probability > last.set_probability);
+    }
+
+    #[test]
+    fn test_possession_chains() {
+        let scout_file = read_test_file();
+        let chains = possession_chains(&scout_file);
+        assert_eq!(chains.len(), 426);
+
+        // The serve, then the visiting side-out attempt blocked by the home team
+        let serve = &chains[0];
+        assert_eq!(serve.team, TeamSide::Home);
+        assert_eq!(serve.serve.unwrap().evaluation, Evaluation::Plus);
+        assert_eq!(serve.won, Some(true));
+
+        let side_out = &chains[1];
+        assert_eq!(side_out.team, TeamSide::Visiting);
+        assert!(side_out.is_side_out());
+        assert_eq!(side_out.serve.unwrap().team, TeamSide::Home);
+        assert_eq!(side_out.reception_evaluation(), Some(Evaluation::Minus));
+        assert_eq!(side_out.set.unwrap().player_number, 8);
+        assert_eq!(side_out.attack_combination(), Some("X5"));
+        assert_eq!(side_out.blockers(), Some(2));
+        assert_eq!(
+            side_out.opponent_block.unwrap().evaluation,
+            Evaluation::Plus
+        );
+        assert_eq!(side_out.won, Some(false));
+
+        assert_eq!(chains[2].attack_combination(), Some("V5"));
+        assert_eq!(chains[2].block, side_out.opponent_block);
+
+        // K1 is defined with a quick run, KB is used without a definition
+        let call = |code: &str| {
+            chains
+                .iter()
+                .find(|chain| chain.setter_call() == Some(code))
+                .unwrap()
+        };
+        assert_eq!(call("K1").quick_available(&scout_file), Some(true));
+        assert_eq!(call("KB").quick_available(&scout_file), None);
+        assert_eq!(chains[0].quick_available(&scout_file), None);
+
+        let all = chain_attack_statistics(&chains);
+        assert_eq!(all.attempts(), 190);
+
+        let side_out = chain_attack_statistics(chains.iter().filter(|chain| chain.is_side_out()));
+        assert_eq!((side_out.attempts(), side_out.kills()), (93, 29));
+
+        let good_reception_quick = chain_attack_statistics(chains.iter().filter(|chain| {
+            chain.reception_evaluation() == Some(Evaluation::Plus)
+                && chain.quick_available(&scout_file) == Some(true)
+        }));
+        assert_eq!(
+            (
+                good_reception_quick.attempts(),
+                good_reception_quick.kills()
+            ),
+            (29, 9)
+        );
+    }
+
+    #[test]
+    fn test_defense_statistics() {
+        let scout_file = read_test_file();
+        let teams = defense_statistics(&scout_file);
+        let (home, visiting) = (&teams[0], &teams[1]);
+        assert_eq!(home.team, TeamSide::Home);
+
+        // Every home attack sorted by blockers, None for attacks without a count
+        let attacks: u32 = home
+            .attacks_by_blockers
+            .iter()
+            .map(|group| group.statistics.attempts())
+            .sum();
+        assert_eq!(attacks, 93);
+        assert_eq!(visiting.opponent_attacks, 93);
+        assert_eq!(home.attacks_by_blockers[0].key, None);
+
+        let two_blockers = home
+            .attacks_by_blockers
+            .iter()
+            .find(|group| group.key == Some(2))
+            .unwrap();
+        assert_eq!(
+            (
+                two_blockers.statistics.attempts(),
+                two_blockers.statistics.kills()
+            ),
+            (50, 19)
+        );
+
+        assert_eq!(home.block.total(), 23);
+        assert_eq!(home.opponent_attacks, 97);
+        // Two of the 8 blocks of number 11 are `=` or `/` and did not touch the ball
+        assert_eq!(home.player_block_touch_rate(11), Some(6.0 * 100.0 / 97.0));
+        let touches = home.block.total() - home.block.equal - home.block.slash;
+        assert_eq!(home.block_touch_rate(), Some(touches as f64 * 100.0 / 97.0));
+
+        assert_eq!(
+            home.dig(DigSituation::Cover).unwrap().evaluations.total(),
+            9
+        );
+        assert_eq!(
+            home.dig(DigSituation::BlockTouched)
+                .unwrap()
+                .positive_percentage(),
+            Some(100.0)
+        );
+
+        assert_eq!(home.free_balls.free_balls, 9);
+        assert_eq!(home.free_balls.kills, 6);
+        assert_eq!(home.free_balls.won, 7);
+
+        let player_free_balls: u32 = home
+            .players
+            .iter()
+            .map(|player| player.free_balls.free_balls)
+            .sum();
+        assert_eq!(player_free_balls, 9);
+    }
+
+    #[test]
+    fn test_serve_styles() {
+        let scout_file = read_test_file();
+
+        assert_eq!(
+            ServeStyle::from_action_type(ActionType::Medium),
+            ServeStyle::JumpFloat
+        );
+        assert_eq!(
+            ServeStyle::from_action_type(ActionType::Quick).name(),
+            "Jump"
+        );
+
+        // *19SM+ is the first serve of the match
+        let serve = scout_file.touches().next().unwrap();
+        assert_eq!(serve.serve_style(), Some(ServeStyle::JumpFloat));
+        assert_eq!(scout_file.touches().nth(1).unwrap().serve_style(), None);
+
+        let reports = serve_style_statistics(&scout_file);
+        let (home, visiting) = (&reports[0], &reports[1]);
+
+        let styles = home
+            .styles
+            .iter()
+            .map(|statistics| (statistics.style, statistics.serves.total()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            styles,
+            vec![
+                (ServeStyle::Float, 9),
+                (ServeStyle::JumpFloat, 62),
+                (ServeStyle::Jump, 3)
+            ]
+        );
+
+        let jump_float = home.style(ServeStyle::JumpFloat).unwrap();
+        assert_eq!(jump_float.ace_percentage(), Some(5.0 * 100.0 / 62.0));
+        assert_eq!(jump_float.error_percentage(), Some(3.0 * 100.0 / 62.0));
+        assert_eq!(jump_float.receptions.total(), 59);
+
+        let topspin = visiting.style(ServeStyle::Topspin).unwrap();
+        assert_eq!(topspin.serves.total(), 10);
+        assert!(visiting.style(ServeStyle::Float).is_none());
+
+        let player_serves: u32 = visiting
+            .players
+            .iter()
+            .flat_map(|player| player.styles.iter())
+            .map(|statistics| statistics.serves.total())
+            .sum();
+        assert_eq!(player_serves, 51);
+    }
+
+    #[test]
+    fn test_skill_action_types() {
+        let scout_file = read_test_file();
+        let mut touches = scout_file.touches();
+
+        // *19SM+ then a02RM- and a08ET#
+        let serve = touches.next().unwrap();
+        assert_eq!(
+            serve.skill_action_type(),
+            SkillActionType::Serve(ServeStyle::JumpFloat)
+        );
+        assert_eq!(serve.action_type_description(), "Jump float serve");
+        assert_eq!(serve.tempo(), None);
+
+        let reception = touches.next().unwrap();
+        assert_eq!(
+            reception.action_type_description(),
+            "Reception of a jump float serve"
+        );
+
+        let set = touches.next().unwrap();
+        assert_eq!(set.skill_action_type(), SkillActionType::Set(Tempo::Tense));
+        assert_eq!(set.tempo(), Some(Tempo::Tense));
+
+        assert_eq!(
+            SkillActionType::new(Skill::Attack, ActionType::Quick).description(),
+            "Quick attack"
+        );
+        assert_eq!(
+            SkillActionType::new(Skill::Dig, ActionType::High).description(),
+            "Dig of a high attack"
+        );
+        assert_eq!(
+            SkillActionType::new(Skill::Block, ActionType::Medium),
+            SkillActionType::Block(Tempo::Half)
+        );
+    }
 }